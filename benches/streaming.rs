@@ -0,0 +1,75 @@
+//! Compares the way `ItemList` used to hand its items to rofi, rendering them all into a list of
+//! strings joined into one and finding the selection back by its text, with the public
+//! `write_options` windows stream their options through now, the selection being the row index
+//! rofi returns. Run with `cargo bench --bench streaming`
+use std::fmt::{self, Display};
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rustofi::window::write_options;
+
+const ROWS: usize = 1_000_000;
+
+/// an item the way an application would keep it, shown through `Display`
+#[derive(Clone)]
+struct Entry {
+    id: usize,
+    name: String
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>7} {}", self.id, self.name)
+    }
+}
+
+/// the built-in rows shown after the items
+const EXTRA: [&str; 2] = ["", "[cancel]"];
+
+/// `ItemList::display` before options were streamed: the options are collected, cloned for
+/// `Window::show` which strips their newlines and joins them, and the selected text is looked
+/// up again among clones of the items
+fn collected(items: &[Entry]) -> io::Result<usize> {
+    let mut display_options: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+    display_options.extend(EXTRA.iter().map(|e| e.to_string()));
+    let shown = display_options.clone();
+    let options_arr = shown
+        .iter()
+        .map(|s| s.replace('\n', ""))
+        .collect::<Vec<String>>()
+        .join("\n");
+    io::sink().write_all(options_arr.as_bytes())?;
+    // rofi answers with the text of the last item
+    let input = &display_options[items.len() - 1];
+    let position = items.iter().cloned().position(|item| *input == item.to_string());
+    Ok(position.unwrap_or(0))
+}
+
+/// the options written by `write_options` as windows do now, rofi answers with the row index
+fn streamed(items: &[Entry]) -> io::Result<usize> {
+    let extra = EXTRA.iter().map(|e| e as &dyn Display);
+    write_options(io::sink(), items.iter().map(|i| i as &dyn Display).chain(extra))?;
+    Ok(items.len() - 1)
+}
+
+fn time<F: FnMut() -> io::Result<usize>>(name: &str, runs: u32, mut f: F) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..runs {
+        let start = Instant::now();
+        black_box(f().expect("writing to a sink can't fail"));
+        best = best.min(start.elapsed());
+    }
+    println!("{:<14} {:>10.1?}", name, best);
+    best
+}
+
+fn main() {
+    let items: Vec<Entry> = (0..ROWS)
+        .map(|id| Entry { id, name: format!("item number {}", id) })
+        .collect();
+    println!("{} rows, best of 5", ROWS);
+    let before = time("collected", 5, || collected(&items));
+    let after = time("streamed", 5, || streamed(&items));
+    println!("{:.1}x faster", before.as_secs_f64() / after.as_secs_f64());
+}
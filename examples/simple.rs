@@ -1,6 +1,6 @@
 // examples/simple.rs
 use rustofi::components::ItemList;
-use rustofi::RustofiResult;
+use rustofi::{CallbackResult, RustofiResult};
 
 fn simple_app() -> RustofiResult {
     // create a list of strings to pass as rofi options. Note that this can be any type you want,
@@ -14,10 +14,10 @@ fn simple_app() -> RustofiResult {
     ItemList::new(rustofi_entries, Box::new(simple_callback)).display("Select an entry".to_string())
 }
 
-pub fn simple_callback(s: &String) -> RustofiResult {
+pub fn simple_callback(s: &mut String) -> CallbackResult {
     // when an item is clicked, print the name!
     println!("Clicked on item: {}", s);
-    RustofiResult::Success
+    Ok(())
 }
 
 fn main() {
@@ -27,7 +27,6 @@ fn main() {
             RustofiResult::Error(_) => break,
             RustofiResult::Exit => break,
             RustofiResult::Cancel => break,
-            RustofiResult::Blank => break,
             _ => {}
         }
     }
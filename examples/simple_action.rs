@@ -1,7 +1,4 @@
 // examples/simple_action.rs
-use std::io::{Error, ErrorKind};
-use std::result::Result;
-
 use rustofi::components::ActionList;
 use rustofi::CallbackResult;
 use rustofi::RustofiResult;
@@ -41,11 +38,11 @@ pub fn simple_callback(person: &Person, action: &String) -> CallbackResult {
         println!("invalid action!");
         return Err("invalid action".to_string());
     }
-    return Ok(());
+    Ok(())
 }
 
 fn main() {
-    let mut p = Person {
+    let p = Person {
         age: 15,
         name: "joe".to_string()
     };
@@ -55,7 +52,6 @@ fn main() {
             RustofiResult::Error(_) => break,
             RustofiResult::Exit => break,
             RustofiResult::Cancel => break,
-            RustofiResult::Blank => break, // we could give the blank entry special powers
             _ => {}
         }
     }
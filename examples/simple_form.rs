@@ -0,0 +1,45 @@
+// examples/simple_form.rs
+use rustofi::components::{Field, Form};
+
+// the struct being filled in needs to be Clone so cancelling can restore the original
+#[derive(Clone, Debug)]
+pub struct Task {
+    pub name: String,
+    pub priority: u8,
+    pub urgent: bool,
+    pub list: String
+}
+
+fn main() {
+    let task = Task {
+        name: "water the plants".to_string(),
+        priority: 3,
+        urgent: false,
+        list: "Home".to_string()
+    };
+    // each field reads its value from the task and writes the edited value back
+    let fields = vec![
+        Field::text("Name", |t: &Task| t.name.clone(), |t, v| t.name = v),
+        Field::parsed("Priority", |t: &Task| t.priority, |t, v| t.priority = v),
+        Field::toggle("Urgent", |t: &Task| t.urgent, |t, v| t.urgent = v),
+        Field::choice(
+            "List",
+            vec!["Home".to_string(), "Work".to_string()],
+            |t: &Task| t.list.clone(),
+            |t, v| t.list = v
+        ),
+    ];
+    let result = Form::new(task, fields)
+        .validate(Box::new(|t| {
+            if t.name.is_empty() {
+                return Err("a task needs a name".to_string());
+            }
+            Ok(())
+        }))
+        .display("Edit task".to_string());
+    match result {
+        Ok(Some(task)) => println!("saved {:?}", task),
+        Ok(None) => println!("cancelled, nothing changed"),
+        Err(e) => println!("error: {}", e)
+    }
+}
@@ -18,5 +18,5 @@ fn fizzbuzz() -> Vec<String> {
 
 fn main() {
     // create a window with 8 lines and a vector of strings and show it
-    Window::new("FizzBuzz in Rofi!").lines(8).show(fizzbuzz());
+    let _ = Window::new("FizzBuzz in Rofi!").lines(8).show(fizzbuzz());
 }
\ No newline at end of file
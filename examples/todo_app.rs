@@ -1,48 +1,28 @@
-#[macro_use]
-extern crate lazy_static;
-
-use pickledb::{PickleDb, PickleDbDumpPolicy, SerializationMethod};
-use rustofi::components::ActionList;
+use rustofi::components::Confirm;
 use rustofi::components::EntryBox;
 use rustofi::components::ItemList;
+use rustofi::editor::InlineEdit;
+use rustofi::group::Grouping;
+use rustofi::store::{FileStore, Format, Store};
 use rustofi::window::{Dimensions, Location, Window};
+use rustofi::Action;
 use rustofi::AppPage;
 use rustofi::CallbackResult;
+use rustofi::PrimaryAction;
+use rustofi::RofiMenu;
 use rustofi::RustofiComponent;
 use rustofi::RustofiResult;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self};
 
 use std::string::ToString;
-use std::sync::Mutex;
 
-fn get_db() -> PickleDb {
-    let mut db;
-    match PickleDb::load(
-        "todo.db",
-        PickleDbDumpPolicy::AutoDump,
-        SerializationMethod::Json
-    ) {
-        Ok(pickledb) => db = pickledb,
-        Err(_) => {
-            db = PickleDb::new(
-                "todo.db",
-                PickleDbDumpPolicy::AutoDump,
-                SerializationMethod::Json
-            )
-        }
-    };
-    if let false = db.lexists("TodoList") {
-        db.lcreate("TodoList").expect("Failed to create DB");
-    }
-    db
+// the todo list is kept in $XDG_DATA_HOME/rustofi/store/todo.json
+fn todo_store() -> FileStore<TodoItem> {
+    FileStore::new("todo", Format::Json)
 }
 
-lazy_static! {
-    static ref DB: Mutex<PickleDb> = { Mutex::new(get_db()) };
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum TodoStatus {
     Todo,
     Complete
@@ -56,7 +36,7 @@ impl fmt::Display for TodoStatus {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TodoItem {
     pub status: TodoStatus,
     pub task: String
@@ -82,7 +62,10 @@ fn create_window() -> Window<'static> {
     Window::new("Today's Todo list")
         .format('i')
         .location(Location::MiddleCentre)
-        .message("Select an item to mark it as complete, select the blank row to add a new item")
+        .message(
+            "Select an item to mark it as complete or press Alt+e to rename it, select [add] or \
+             press Alt+a to add a new item"
+        )
         .dimensions(Dimensions {
             width: 720,
             height: 640,
@@ -99,107 +82,107 @@ pub enum TodoState {
     Exit
 }
 
+// each variant becomes one of the page's actions, the label is what rofi shows
+#[derive(Clone, RofiMenu)]
 pub enum TodoAction {
-    Add,
+    #[rofi(label = "[delete]")]
     Delete
 }
 
 pub struct TodoApp {}
 
 impl TodoApp {
-    pub fn add(t: &TodoItem) {
-        DB.lock().unwrap().ladd("TodoList", &t);
-    }
     pub fn delete(t: &mut TodoItem) -> CallbackResult {
-        DB.lock().unwrap().lrem_value("TodoList", &t);
-        Ok(())
+        if !Confirm::new().display(format!("Delete '{}'?", t.task)) {
+            return Ok(());
+        }
+        let mut store = todo_store();
+        let mut todos = store.load()?;
+        todos.retain(|todo| todo != t);
+        store.save(&todos)
     }
-    pub fn add_item(task: String) {
-        if task == "" {
-            return;
+    pub fn add_item(task: String) -> CallbackResult {
+        if task.is_empty() {
+            return Ok(());
         }
-        DB.lock().unwrap().ladd(
-            "TodoList",
-            &TodoItem {
-                status: TodoStatus::Todo,
-                task
-            }
-        );
+        let mut store = todo_store();
+        let mut todos = store.load()?;
+        todos.push(TodoItem {
+            status: TodoStatus::Todo,
+            task
+        });
+        store.save(&todos)
     }
 
     pub fn toggle_todo(t: &mut TodoItem) -> CallbackResult {
         println!("Marking task: {}", t);
-
-        // have to remove item then readd it
-        match DB.lock().unwrap().lrem_value("TodoList", &t) {
-            Ok(_) => {}
-            Err(_) => return Err("error toggling todo".to_string())
-        };
+        // the page saves the changed item back to the store
         t.toggle();
-        TodoApp::add(t);
         Ok(())
     }
 
-    pub fn show_todos() -> RustofiResult {
-        // add all TodoItems to the list of rofi selections
-        let mut todos = Vec::new();
-        for item_iter in DB.lock().unwrap().liter("TodoList") {
-            let item = item_iter.get_item::<TodoItem>().unwrap();
-            todos.push(item);
-        }
-        AppPage::<TodoItem>::new(
-            todos,
-            Box::new(TodoApp::toggle_todo),
-            vec!["[add]".to_string(), "[delete]".to_string()]
-        )
-        .window(create_window())
-        .display("Todo".to_string())
+    pub fn show_todos() -> RustofiResult<TodoAction> {
+        // the TodoItems are loaded from the store when the page is displayed
+        AppPage::new(Vec::new(), Box::new(TodoApp::toggle_todo), Action::menu_entries())
+            .store(todo_store())
+            .primary(PrimaryAction::new("[add]").keybinding("Alt+a"))
+            .edit(InlineEdit::new(
+                "Alt+e",
+                Box::new(|t: &TodoItem| t.task.clone()),
+                Box::new(|task: &str, t: &TodoItem| match task.trim() {
+                    "" => Err("a todo needs a task".to_string()),
+                    task => Ok(TodoItem {
+                        status: t.status.clone(),
+                        task: task.to_string()
+                    })
+                })
+            ))
+            .grouping(
+                Grouping::by(Box::new(|t: &TodoItem| t.status.to_string()))
+                    .order(vec!["TODO".to_string(), "COMPLETE".to_string()])
+            )
+            .window(create_window())
+            .display("Todo".to_string())
     }
     pub fn delete_todos() -> RustofiResult {
-        let mut todos = Vec::new();
-        for item_iter in DB.lock().unwrap().liter("TodoList") {
-            let item = item_iter.get_item::<TodoItem>().unwrap();
-            todos.push(item);
-        }
+        // not backed by the store: the callback removes the todo from it itself
+        let todos = match todo_store().load() {
+            Ok(todos) => todos,
+            Err(m) => return RustofiResult::Error(m)
+        };
         ItemList::<TodoItem>::new(todos, Box::new(TodoApp::delete))
             .display("Select a Todo to delete".to_string())
     }
 
     pub fn add_todo() -> RustofiResult {
-        EntryBox::display("Enter a new Todo".to_string())
+        EntryBox::new()
+            .placeholder("what needs doing?".to_string())
+            .display("Enter a new Todo".to_string())
     }
 }
 
 /// cargo run --example `todo_app`
-/// When the rofi menu appears, click the blank entry to create a new todo_list item
+/// When the rofi menu appears, select `[add]` to create a new todo_list item
 fn main() {
     let mut state = TodoState::Root;
     loop {
         state = match state {
             // handle input in the root state that lists
             // all todos and [delete], [add], [exit]
-            TodoState::Root => match TodoApp::show_todos() {
-                RustofiResult::Action(a) => {
-                    if a == "[add]".to_string() {
-                        // switch to the add todo page next 'frame'
-                        TodoState::Add
-                    } else if a == "[delete]".to_string() {
-                        TodoState::Delete
-                    } else {
-                        // exit next frame
-                        TodoState::Exit
-                    }
-                }
+            TodoState::Root => match TodoApp::show_todos().display_error() {
+                // switch to the add todo page next 'frame'
+                RustofiResult::Primary => TodoState::Add,
+                RustofiResult::Action(TodoAction::Delete) => TodoState::Delete,
                 // marked as done, continue displaying list
-                RustofiResult::Selection(s) => TodoState::Root,
-                _ => TodoState::Exit // something weird happened, just exit
+                RustofiResult::Selection(_) => TodoState::Root,
+                _ => TodoState::Exit // exit or something weird happened, just exit
             },
             // handle input in the add state
             TodoState::Add => match TodoApp::add_todo() {
-                RustofiResult::Selection(s) => {
-                    TodoApp::add_item(s);
-                    TodoState::Root // todo added, return to main page
-                }
+                RustofiResult::Selection(s) => match TodoApp::add_item(s) {
+                    Ok(_) => TodoState::Root, // todo added, return to main page
+                    Err(_) => break
+                },
                 // todo cancelled, return to main page
                 RustofiResult::Cancel => TodoState::Root,
                 _ => break // something weird happened, crash down!
@@ -207,13 +190,12 @@ fn main() {
             // handle input in the delete state
             TodoState::Delete => match TodoApp::delete_todos() {
                 // deleted
-                RustofiResult::Selection(s) => TodoState::Root,
+                RustofiResult::Selection(_) => TodoState::Root,
                 // cancelled
                 RustofiResult::Cancel => TodoState::Root,
                 _ => break
             },
-            TodoState::Exit => break,
-            _ => break
+            TodoState::Exit => break
         };
     }
 }
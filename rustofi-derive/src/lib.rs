@@ -0,0 +1,213 @@
+//! Derive macros for [rustofi](https://crates.io/crates/rustofi). Use them through the `derive`
+//! feature of rustofi rather than depending on this crate directly.
+//!
+//! `#[derive(RofiMenu)]` turns an enum of unit variants into a menu, each variant becoming an
+//! entry. `#[derive(RofiForm)]` turns a struct with named fields into a `Form`, each field becoming
+//! an editable row. Both accept a `#[rofi(...)]` attribute on variants and fields:
+//!
+//! - `label = "..."` text shown instead of the variant or field name
+//! - `icon = "..."` icon shown next to a menu entry
+//! - `skip` leave a variant or field out
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitStr, Type};
+
+/// options given through `#[rofi(...)]`
+#[derive(Default)]
+struct RofiAttrs {
+    label: Option<String>,
+    icon: Option<String>,
+    skip: bool
+}
+
+impl RofiAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = RofiAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("rofi")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("label") {
+                    result.label = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("icon") {
+                    result.icon = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else {
+                    return Err(meta.error("expected `label`, `icon` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+/// derive `rustofi::RofiMenu` for an enum of unit variants
+#[proc_macro_derive(RofiMenu, attributes(rofi))]
+pub fn derive_rofi_menu(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match rofi_menu(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn rofi_menu(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(syn::Error::new_spanned(name, "RofiMenu can only be derived for enums"))
+    };
+    let mut entries = Vec::new();
+    let mut labels = Vec::new();
+    let mut icons = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "RofiMenu variants can't hold any data"
+            ));
+        }
+        let attrs = RofiAttrs::parse(&variant.attrs)?;
+        let ident = &variant.ident;
+        let label = attrs.label.unwrap_or_else(|| ident.to_string());
+        labels.push(quote! { #name::#ident => #label.to_string() });
+        icons.push(match attrs.icon {
+            Some(icon) => quote! { #name::#ident => Some(#icon.to_string()) },
+            None => quote! { #name::#ident => None }
+        });
+        if !attrs.skip {
+            entries.push(quote! { #name::#ident });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rustofi::RofiMenu for #name #ty_generics #where_clause {
+            fn entries() -> Vec<Self> {
+                vec![#(#entries),*]
+            }
+
+            fn label(&self) -> String {
+                match self {
+                    #(#labels),*
+                }
+            }
+
+            fn icon(&self) -> Option<String> {
+                match self {
+                    #(#icons),*
+                }
+            }
+        }
+    })
+}
+
+/// derive `rustofi::RofiForm` for a struct with named fields
+#[proc_macro_derive(RofiForm, attributes(rofi))]
+pub fn derive_rofi_form(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match rofi_form(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn rofi_form(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "RofiForm needs named fields"))
+        },
+        _ => return Err(syn::Error::new_spanned(name, "RofiForm can only be derived for structs"))
+    };
+    let mut rows = Vec::new();
+    for field in fields {
+        let attrs = RofiAttrs::parse(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let label = attrs.label.unwrap_or_else(|| field_label(&ident.to_string()));
+        let constructor = match type_name(&field.ty).as_deref() {
+            Some("String") => quote! { text },
+            Some("bool") => quote! { toggle },
+            _ => quote! { parsed }
+        };
+        rows.push(quote! {
+            ::rustofi::components::Field::#constructor(
+                #label,
+                |s: &Self| s.#ident.clone(),
+                |s: &mut Self, v| s.#ident = v
+            )
+        });
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rustofi::RofiForm for #name #ty_generics #where_clause {
+            fn fields() -> Vec<::rustofi::components::Field<Self>> {
+                vec![#(#rows),*]
+            }
+        }
+    })
+}
+
+/// last path segment of a type, `String` for both `String` and `std::string::String`
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None
+    }
+}
+
+/// turn a field name like `due_date` into a label like `Due date`
+fn field_label(name: &str) -> String {
+    let name = name.trim_start_matches("r#").replace('_', " ");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_labels() {
+        assert_eq!(field_label("title"), "Title");
+        assert_eq!(field_label("due_date"), "Due date");
+        assert_eq!(field_label("r#type"), "Type");
+        assert_eq!(field_label("_private"), " private");
+        assert_eq!(field_label(""), "");
+    }
+
+    #[test]
+    fn type_names() {
+        let name = |ty: &str| type_name(&syn::parse_str::<Type>(ty).unwrap());
+        assert_eq!(name("String").as_deref(), Some("String"));
+        assert_eq!(name("std::string::String").as_deref(), Some("String"));
+        assert_eq!(name("Vec<bool>").as_deref(), Some("Vec"));
+        assert_eq!(name("&str"), None);
+    }
+
+    #[test]
+    fn menus_need_unit_variants_of_an_enum() {
+        let input = |src: &str| syn::parse_str::<DeriveInput>(src).unwrap();
+        assert!(rofi_menu(&input("enum A { B, C }")).is_ok());
+        assert!(rofi_menu(&input("enum A { B(u8) }")).is_err());
+        assert!(rofi_menu(&input("struct A;")).is_err());
+        assert!(rofi_menu(&input("enum A { #[rofi(colour = \"red\")] B }")).is_err());
+    }
+
+    #[test]
+    fn forms_need_named_fields() {
+        let input = |src: &str| syn::parse_str::<DeriveInput>(src).unwrap();
+        assert!(rofi_form(&input("struct A { b: String }")).is_ok());
+        assert!(rofi_form(&input("struct A(String);")).is_err());
+        assert!(rofi_form(&input("enum A { B }")).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! An `Actionable` type lists the actions it offers on each of its values and carries them out, so
+//! a list of them doesn't need an `ActionList` wired to it by hand. Given to an `AppPage` or
+//! `ItemList` with `item_actions`, selecting an item opens a menu of its actions and once the
+//! chosen action ran, or the menu was left, the list is shown again.
+//!
+//! Actions are plain `Action`s: they can carry an icon, a description and a keybinding, ask for
+//! confirmation or be disabled, which shows them greyed out.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::actionable::Actionable;
+//! use rustofi::components::ItemList;
+//! use rustofi::{Action, CallbackResult};
+//! use std::fmt;
+//!
+//! #[derive(Clone)]
+//! struct Todo {
+//!     task: String,
+//!     done: bool
+//! }
+//!
+//! impl fmt::Display for Todo {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         write!(f, "[{}] {}", if self.done { "x" } else { " " }, self.task)
+//!     }
+//! }
+//!
+//! impl Actionable for Todo {
+//!     type Action = String;
+//!
+//!     fn actions(&self) -> Vec<Action<String>> {
+//!         vec![
+//!             Action::new("complete".to_string()).enabled(!self.done),
+//!             Action::new("reopen".to_string()).enabled(self.done).confirm(true)
+//!         ]
+//!     }
+//!
+//!     fn execute(&mut self, action: &String) -> CallbackResult {
+//!         self.done = action == "complete";
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let todos = vec![Todo { task: "buy milk".to_string(), done: false }];
+//! ItemList::new(todos, Box::new(|_| Ok(())))
+//!     .item_actions()
+//!     .display("Todos".to_string());
+//! ```
+use std::fmt::Display;
+
+use crate::guard::guarded;
+use crate::labels::{BuiltIn, Labels};
+use crate::window::{Location, Response, Window};
+use crate::{bound_to_key, keybinding_args, Action, Bound, CallbackResult};
+
+/// a type offering actions on each of its values
+pub trait Actionable {
+    /// type of the actions
+    type Action;
+
+    /// the actions offered on this value, in display order
+    fn actions(&self) -> Vec<Action<Self::Action>>;
+
+    /// carry out `action` on this value
+    fn execute(&mut self, action: &Self::Action) -> CallbackResult;
+}
+
+/// shows the menu of an item and runs the action chosen, returning whether one ran
+pub type ItemMenu<T> = Box<dyn FnMut(&mut T, &Labels) -> Result<bool, String>>;
+
+/// the `ItemMenu` of an `Actionable` type
+pub(crate) fn item_menu<T: Actionable + Display + 'static>() -> ItemMenu<T> {
+    Box::new(|item: &mut T, labels: &Labels| show_actions(item, labels))
+}
+
+/// show the actions of `item` followed by `[back]`, then confirm and execute the one selected
+fn show_actions<T: Actionable + Display>(item: &mut T, labels: &Labels) -> Result<bool, String> {
+    let mut actions = item.actions();
+    let mut display_options: Vec<String> = actions.iter().map(|a| a.row()).collect();
+    display_options.extend(labels.label(BuiltIn::Back));
+    let response = Window::new("Actions")
+        .format('s')
+        .location(Location::MiddleCentre)
+        .add_args(vec!["-markup-rows".to_string()])
+        .lines(display_options.len() as i32)
+        .prompt(item.to_string())
+        .add_args(keybinding_args(None, &[], &actions, None))
+        .select(display_options);
+    let index = match response {
+        Ok(Response::Row(row)) if row < actions.len() => row,
+        Ok(Response::Key { key, .. }) => match bound_to_key(None, &[], &actions, None, key) {
+            Some(Bound::Action(index)) => index,
+            _ => return Ok(false)
+        },
+        // `[back]`, a custom entry or closing the window
+        Ok(_) => return Ok(false),
+        Err(_) => return Err("error getting user input from rofi".to_string())
+    };
+    let action = &mut actions[index];
+    if !action.trigger()? {
+        return Ok(false);
+    }
+    guarded(|| item.execute(&action.value))?;
+    Ok(true)
+}
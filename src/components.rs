@@ -1,5 +1,6 @@
-//! `ItemList`, `ActionList` and `EntryBox` are additional components or controls you can use to build
-//! your application.
+//! `ItemList`, `ActionList`, `EntryBox`, `AutocompleteEntry`, `Form`, `Checklist`, `Table`,
+//! `Confirm` and `MessageBox` are additional components or controls you can use to build your
+//! application.
 //!
 //! # Examples
 //!
@@ -11,7 +12,7 @@
 //! ```no_run
 //! // examples/simple.rs
 //! use rustofi::components::ItemList;
-//! use rustofi::RustofiResult;
+//! use rustofi::{CallbackResult, RustofiResult};
 //!
 //! fn simple_app() -> RustofiResult {
 //!     // create a list of strings to pass as rofi options. Note that this can be any type you want,
@@ -25,20 +26,19 @@
 //!     ItemList::new(rustofi_entries, Box::new(simple_callback)).display("Select an entry".to_string())
 //! }
 //!
-//! pub fn simple_callback(s: &String) -> RustofiResult {
+//! pub fn simple_callback(s: &mut String) -> CallbackResult {
 //!     // when an item is clicked, print the name!
 //!     println!("Clicked on item: {}", s);
-//!     RustofiResult::Success
+//!     Ok(())
 //! }
 //!
 //! fn main() {
 //!     loop {
 //!         match simple_app() {
 //!             //!  loop unless the user requests we exit
-//!             RustofiResult::Error => break,
+//!             RustofiResult::Error(_) => break,
 //!             RustofiResult::Exit => break,
 //!             RustofiResult::Cancel => break,
-//!             RustofiResult::Blank => break,
 //!             _ => {}
 //!         }
 //!     }
@@ -54,7 +54,7 @@
 //! ```no_run
 //! // examples/simple_action.rs
 //! use rustofi::components::ActionList;
-//! use rustofi::RustofiResult;
+//! use rustofi::{CallbackResult, RustofiResult};
 //!
 //! // notice the Clone derive and Display implementation? These are
 //! // necessary if you want to pass in a custom type!
@@ -79,32 +79,31 @@
 //!         .display(format!("looking at {}, age {}", person.name, person.age))
 //! }
 //!
-//! pub fn simple_callback(person: &Person, action: &String) -> RustofiResult {
+//! pub fn simple_callback(person: &Person, action: &String) -> CallbackResult {
 //!     println!("selected action: {}", action);
 //!     // match which action was selected
 //!     if action == "Age Up" {
-//!         println!("{} age + 5 is: {} ", person.name, person.age);
+//!         println!("{} age + 5 is: {} ", person.name, person.age + 5);
 //!     } else if action == "Age Down" {
-//!         println!("{} age - 5 is: {}", person.name, person.age);
+//!         println!("{} age - 5 is: {}", person.name, person.age - 5);
 //!     } else { // user entered a custom string
 //!         println!("invalid action!");
-//!         return RustofiResult::Error;
+//!         return Err("invalid action".to_string());
 //!     }
-//!     RustofiResult::Success
+//!     Ok(())
 //! }
 //!
 //! fn main() {
-//!     let mut p = Person {
+//!     let p = Person {
 //!         age: 15,
 //!         name: "joe".to_string()
 //!     };
 //!     loop {
 //!         match simple_app(p.clone()) {
 //!             // loop until an exit or error occurs
-//!             RustofiResult::Error => break,
+//!             RustofiResult::Error(_) => break,
 //!             RustofiResult::Exit => break,
 //!             RustofiResult::Cancel => break,
-//!             RustofiResult::Blank => break, // we could give the blank entry special powers
 //!             _ => {}
 //!         }
 //!     }
@@ -112,8 +111,22 @@
 //! ```
 use std::clone::Clone;
 use std::fmt::Display;
+use std::rc::Rc;
+use std::str::FromStr;
 
-use crate::window::{Location, Window};
+use crate::actionable::{item_menu, Actionable, ItemMenu};
+use crate::guard::guarded;
+use crate::history::History;
+use crate::menu::RofiMenu;
+use crate::editor::InlineEdit;
+use crate::group::{self, GroupRow, Grouping, Line};
+use crate::items::ItemHandlers;
+use crate::labels::{BuiltIn, Labels};
+use crate::store::{load_items, SharedStore, Store};
+use crate::usage::{sort_items, UsageTracker};
+use crate::undo::{undo_rows, UndoStack};
+use crate::window::{escape_markup, Location, Response, Row, Window};
+use crate::{bound_to_key, keybinding_args, Action, Bound, PrimaryAction};
 use crate::{CallbackResult, RustofiCallback, RustofiResult};
 
 /// `ItemList` is a simple rofi window with a selection of items backed by a type `T`. Each item
@@ -121,6 +134,14 @@ use crate::{CallbackResult, RustofiCallback, RustofiResult};
 pub struct ItemList<'a, T> {
     pub items: Vec<T>,
     pub item_callback: Box<dyn RustofiCallback<T>>,
+    pub primary: Option<PrimaryAction>,
+    pub usage: Option<UsageTracker>,
+    pub grouping: Option<Grouping<T>>,
+    pub undo: Option<UndoStack>,
+    pub store: Option<SharedStore<T>>,
+    pub item_menu: Option<ItemMenu<T>>,
+    pub edit: Option<InlineEdit<T>>,
+    pub labels: Option<Labels>,
     pub window: Window<'a>
 }
 
@@ -130,16 +151,26 @@ impl<'a, T: Display + Clone> ItemList<'a, T> {
         ItemList {
             items,
             item_callback,
+            primary: None,
+            usage: None,
+            grouping: None,
+            undo: None,
+            store: None,
+            item_menu: None,
+            edit: None,
+            labels: None,
             window: ItemList::<T>::create_window()
         }
     }
 
-    /// create a simple rofi instance representing a window in the middle of the screen
+    /// create a simple rofi instance representing a window in the middle of the screen, shown
+    /// once the first 100 options are read
     fn create_window() -> Window<'a> {
         Window::new("ItemList")
             .format('s')
             .location(Location::MiddleCentre)
             .add_args(vec!["-markup-rows".to_string()])
+            .async_pre_read(100)
     }
 
     /// set a completely custom window
@@ -148,68 +179,225 @@ impl<'a, T: Display + Clone> ItemList<'a, T> {
         self
     }
 
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// set the primary action, shown right after the items
+    pub fn primary(mut self, primary: PrimaryAction) -> Self {
+        self.primary = Some(primary);
+        self
+    }
+
+    /// show the items most used with `usage` first and record every item selected
+    pub fn usage(mut self, usage: UsageTracker) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// show the items in groups under header rows
+    pub fn grouping(mut self, grouping: Grouping<T>) -> Self {
+        self.grouping = Some(grouping);
+        self
+    }
+
+    /// offer `[undo]` and `[redo]` for the operations recorded in `undo`
+    pub fn undo(mut self, undo: UndoStack) -> Self {
+        self.undo = Some(undo);
+        self
+    }
+
+    /// show the list kept in `store` instead of the items given, loaded every time the window is
+    /// displayed and saved back after the item callback ran
+    pub fn store<S: Store<T> + 'static>(mut self, store: S) -> Self
+    where
+        T: 'static
+    {
+        self.store = Some(SharedStore::new(store));
+        self
+    }
+
+    /// open a menu of the selected item's actions instead of running the item callback, showing
+    /// the list again once it's left
+    pub fn item_actions(mut self) -> Self
+    where
+        T: Actionable + 'static
+    {
+        self.item_menu = Some(item_menu());
+        self
+    }
+
+    /// edit the highlighted item when `edit`'s keybinding is pressed, showing the list again
+    /// afterwards
+    pub fn edit(mut self, edit: InlineEdit<T>) -> Self {
+        self.edit = Some(edit);
+        self
+    }
+
     /// run the constructed rofi command and match the output: Calling the specified callback with
-    /// selected item `T` or returning `Cancel`, `Blank` or `Error`. If the user's entry isn't in
-    /// the list, we return the string back wrapped in a `RustofiResult::Selection`
+    /// selected item `T` (or opening its menu and showing the list again), running the primary
+    /// action or returning `Cancel` or `Error`. If the user's entry isn't in the list, we return
+    /// the string back wrapped in a `RustofiResult::Selection`
     pub fn display(&mut self, prompt: String) -> RustofiResult {
-        let extra = vec!["".to_string(), "[cancel]".to_string()];
-        let mut display_options: Vec<String> = self.items.iter().map(|s| s.to_string()).collect();
-        display_options = display_options.into_iter().chain(extra.clone()).collect();
-        let response = self
-            .window
-            .clone()
-            .lines(display_options.len() as i32)
-            .prompt(prompt)
-            .show(display_options.clone());
-        match response {
-            Ok(input) => {
-                if input == "[cancel]" || input == "" {
-                    RustofiResult::Cancel
-                } else if input == " " {
-                    RustofiResult::Blank
-                } else {
-                    for mut item in self.items.clone() {
-                        if input == item.to_string() {
-                            match (self.item_callback)(&mut item) {
-                                Ok(_) => return RustofiResult::Selection(input),
-                                Err(m) => return RustofiResult::Error(m)
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        // held until the window is done with, so other instances can't change the list meanwhile
+        let _lock = match load_items(&self.store, &mut self.items) {
+            Ok(lock) => lock,
+            Err(m) => return RustofiResult::Error(m)
+        };
+        let order = sort_items(&self.usage, &mut self.items);
+        let mut selected_row = 0;
+        loop {
+            // item menus and edits may record operations, so the undo rows are rebuilt every time
+            let mut extra = undo_rows(&self.undo);
+            extra.extend(&[BuiltIn::Separator, BuiltIn::Cancel]);
+            let extra = labels.rows(&extra);
+            let primary = self.primary.as_ref();
+            let keys: Vec<(Bound, &str)> =
+                self.edit.iter().map(|e| (Bound::Edit, e.keybinding.as_str())).collect();
+            let layout = group::layout(&self.grouping, &self.items);
+            let item_count = layout.len();
+            let primary_row = primary.map(|_| item_count);
+            let first_extra = item_count + primary.is_some() as usize;
+            // rows are streamed to rofi as they're written, selections are found back by index
+            let primary_label = primary.map(|p| p.row());
+            let lines = group::lines(&self.grouping, &layout, &self.items, None)
+                .chain(primary_label.iter().map(|label| Line::Label(label)))
+                .chain(extra.iter().map(|(_, label)| Line::Label(label)));
+            let response = self
+                .window
+                .clone()
+                .lines((first_extra + extra.len()) as i32)
+                .prompt(prompt.clone())
+                .add_args(vec!["-selected-row".to_string(), selected_row.to_string()])
+                .add_args(keybinding_args::<()>(primary, &keys, &[], self.undo.as_ref()))
+                .select(lines);
+            return match response {
+                Ok(Response::Row(row)) if row < item_count => match layout.get(row) {
+                    Some(GroupRow::Item(i)) => {
+                        let (items, mut handlers) = self.handlers();
+                        match handlers.select(&labels, items, i, Some(&order)) {
+                            Err(m) => RustofiResult::Error(m),
+                            Ok(None) => {
+                                selected_row = row;
+                                continue;
+                            }
+                            Ok(Some(selection)) => RustofiResult::Selection(selection)
+                        }
+                    }
+                    // only collapsible groups have selectable headers
+                    Some(GroupRow::Header(name)) => {
+                        if let Some(grouping) = &mut self.grouping {
+                            grouping.toggle(&name);
+                        }
+                        selected_row = row;
+                        continue;
+                    }
+                    None => RustofiResult::Cancel
+                },
+                Ok(Response::Row(row)) if Some(row) == primary_row => self.trigger_primary(),
+                Ok(Response::Row(row)) => match extra.get(row - first_extra).map(|(b, _)| *b) {
+                    Some(BuiltIn::Undo) => self.trigger_undo(false),
+                    Some(BuiltIn::Redo) => self.trigger_undo(true),
+                    _ => RustofiResult::Cancel
+                },
+                Ok(Response::Key { key, row, .. }) => {
+                    let undo = self.undo.as_ref();
+                    match bound_to_key::<()>(primary, &keys, &[], undo, key) {
+                        Some(Bound::Primary) => self.trigger_primary(),
+                        Some(Bound::Edit) => {
+                            let (items, mut handlers) = self.handlers();
+                            let item_row = row.and_then(|row| layout.get(row));
+                            if let Err(m) = handlers.edit(items, item_row, Some(&order)) {
+                                return RustofiResult::Error(m);
                             }
+                            selected_row = row.unwrap_or(0);
+                            continue;
                         }
+                        Some(Bound::Undo) => self.trigger_undo(false),
+                        Some(Bound::Redo) => self.trigger_undo(true),
+                        _ => RustofiResult::Cancel
                     }
-                    RustofiResult::Selection(input)
                 }
-            }
-            Err(_) => RustofiResult::Error("error getting user input from rofi".to_string())
+                Ok(Response::Entry(input)) => RustofiResult::Selection(input),
+                // the separator, cancel entry or closing the window
+                Ok(_) => RustofiResult::Cancel,
+                Err(_) => RustofiResult::Error("error getting user input from rofi".to_string())
+            };
+        }
+    }
+
+    fn trigger_primary(&mut self) -> RustofiResult {
+        match &mut self.primary {
+            Some(primary) => primary.trigger(),
+            None => RustofiResult::Cancel
+        }
+    }
+
+    fn trigger_undo(&self, redo: bool) -> RustofiResult {
+        match &self.undo {
+            Some(undo) => undo.trigger(redo),
+            None => RustofiResult::Cancel
         }
     }
+
+    /// the items along with what acts on them
+    fn handlers(&mut self) -> (&mut Vec<T>, ItemHandlers<'_, T>) {
+        let handlers = ItemHandlers {
+            callback: &mut self.item_callback,
+            menu: &mut self.item_menu,
+            edit: &mut self.edit,
+            store: &self.store,
+            usage: &self.usage,
+            undo: &self.undo
+        };
+        (&mut self.items, handlers)
+    }
 }
 
-/// `ActionList` is a simple rofi window with a selection of strings that operate on a
+/// callback run by an `ActionList` with its item and the selected action
+pub type ActionListCallback<T, A = String> = Box<dyn FnMut(&T, &A) -> CallbackResult>;
+
+/// `ActionList` is a simple rofi window with a selection of actions that operate on a
 /// single item `T`. When a selection is made, the `action_callback` is called with the item and
-/// action name passed as arguments
+/// action passed as arguments. Actions can be of any type `A`, plain strings by default
 ///
-pub struct ActionList<'a, T> {
+pub struct ActionList<'a, T, A = String> {
     pub item: T,
-    pub actions: Vec<String>,
-    pub action_callback: Box<dyn FnMut(&T, &String) -> CallbackResult>,
+    pub actions: Vec<Action<A>>,
+    pub action_callback: ActionListCallback<T, A>,
+    pub undo: Option<UndoStack>,
+    pub labels: Option<Labels>,
     pub window: Window<'a>
 }
 
-impl<'a, T: Display + Clone> ActionList<'a, T> {
-    /// create a new `ActionList` with an item to operate on, a list of strings representing actions
-    /// and a callback to run on selection
-    pub fn new(
-        item: T, actions: Vec<String>,
-        action_callback: Box<dyn FnMut(&T, &String) -> CallbackResult>
+impl<'a, T: Display + Clone, A: Clone> ActionList<'a, T, A> {
+    /// create a new `ActionList` with an item to operate on, a list of actions, either plain
+    /// values or configured `Action`s, and a callback to run on selection
+    pub fn new<I: Into<Action<A>>>(
+        item: T, actions: Vec<I>, action_callback: ActionListCallback<T, A>
     ) -> Self {
         ActionList {
             item,
-            actions,
+            actions: actions.into_iter().map(|a| a.into()).collect(),
             action_callback,
-            window: ActionList::<T>::create_window()
+            undo: None,
+            labels: None,
+            window: ActionList::<T, A>::create_window()
         }
     }
 
+    /// create a new `ActionList` offering every entry of menu `A` as an action
+    pub fn menu<C>(item: T, action_callback: C) -> Self
+    where
+        A: RofiMenu,
+        C: 'static + FnMut(&T, &A) -> CallbackResult
+    {
+        ActionList::new(item, Action::menu_entries(), Box::new(action_callback))
+    }
+
     /// create a simple rofi instance representing a window in the middle of the screen
     fn create_window() -> Window<'a> {
         Window::new("ActionList")
@@ -224,68 +412,1319 @@ impl<'a, T: Display + Clone> ActionList<'a, T> {
         self
     }
 
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// offer `[undo]` and `[redo]` for the operations recorded in `undo`
+    pub fn undo(mut self, undo: UndoStack) -> Self {
+        self.undo = Some(undo);
+        self
+    }
+
     /// run the constructed rofi command and display the window, parsing the selection result
     /// In the case of an empty entry (user exited program most likely) or the cancel entry being
-    /// selected we return `RustofiResult::Cancel` and `RustofiResult::Blank` respectively. In all
-    /// other cases, we attempt to run the given callback on the action. In the case the entry does
-    /// not match the action, we simply return the input wrapped in a `RustofiResult::Action`
-    pub fn display(&mut self, prompt: String) -> RustofiResult {
-        let extra = vec!["".to_string(), "[cancel]".to_string()];
-        let mut display_options: Vec<String> = self.actions.iter().map(|s| s.to_string()).collect();
-        display_options = display_options.into_iter().chain(extra.clone()).collect();
+    /// selected we return `RustofiResult::Cancel`. When an action is selected, directly or
+    /// through its keybinding, we ask for confirmation if it needs it (declining returns
+    /// `RustofiResult::Cancel`), run its own callback if it has one and then the list's
+    /// callback, returning the action wrapped in a `RustofiResult::Action`. In the case the entry
+    /// does not match any action, we simply return the input wrapped in a
+    /// `RustofiResult::Selection`
+    pub fn display(&mut self, prompt: String) -> RustofiResult<A> {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let mut extra = undo_rows(&self.undo);
+        extra.extend(&[BuiltIn::Separator, BuiltIn::Cancel]);
+        let extra = labels.rows(&extra);
+        let mut display_options: Vec<String> = self.actions.iter().map(|a| a.row()).collect();
+        display_options.extend(extra.iter().map(|(_, label)| label.clone()));
         let response = self
             .window
             .clone()
             .lines(display_options.len() as i32)
             .prompt(prompt)
-            .show(display_options.clone());
-        match response {
-            Ok(input) => {
-                if input == "[cancel]" || input == "" {
-                    RustofiResult::Cancel
-                } else if input == " " {
-                    RustofiResult::Blank
-                } else {
-                    for action in self.actions.clone() {
-                        if input == action.to_string() {
-                            match (self.action_callback)(&self.item, &action.to_string()) {
-                                Ok(_) => return RustofiResult::Action(input),
-                                Err(m) => return RustofiResult::Error(m)
-                            }
-                        }
-                    }
-                    RustofiResult::Action(input)
+            .add_args(keybinding_args(None, &[], &self.actions, self.undo.as_ref()))
+            .select(display_options);
+        let index = match response {
+            Ok(Response::Row(row)) if row < self.actions.len() => row,
+            Ok(Response::Row(row)) => {
+                return match extra.get(row - self.actions.len()).map(|(b, _)| *b) {
+                    Some(BuiltIn::Undo) => self.trigger_undo(false),
+                    Some(BuiltIn::Redo) => self.trigger_undo(true),
+                    _ => RustofiResult::Cancel
+                };
+            }
+            Ok(Response::Key { key, .. }) => {
+                match bound_to_key(None, &[], &self.actions, self.undo.as_ref(), key) {
+                    Some(Bound::Action(index)) => index,
+                    Some(Bound::Undo) => return self.trigger_undo(false),
+                    Some(Bound::Redo) => return self.trigger_undo(true),
+                    _ => return RustofiResult::Cancel
                 }
             }
-            Err(_) => RustofiResult::Error("error getting user input from rofi".to_string())
+            Ok(Response::Entry(input)) => return RustofiResult::Selection(input),
+            Ok(_) => return RustofiResult::Cancel,
+            Err(_) => {
+                return RustofiResult::Error("error getting user input from rofi".to_string())
+            }
+        };
+        let action = &mut self.actions[index];
+        match action.trigger() {
+            Ok(true) => {}
+            Ok(false) => return RustofiResult::Cancel,
+            Err(m) => return RustofiResult::Error(m)
+        }
+        let (item, callback) = (&self.item, &mut self.action_callback);
+        match guarded(|| callback(item, &action.value)) {
+            Ok(_) => RustofiResult::Action(action.value.clone()),
+            Err(m) => RustofiResult::Error(m)
+        }
+    }
+
+    fn trigger_undo(&self, redo: bool) -> RustofiResult<A> {
+        match &self.undo {
+            Some(undo) => undo.trigger(redo),
+            None => RustofiResult::Cancel
         }
     }
 }
 
-/// empty struct representing a rofi window used to take and return user input as a string
-pub struct EntryBox {}
+/// `EntryBox` is a rofi window used to take and return user input as a string. It can be prefilled
+/// with an initial value, show a placeholder, hide the input for secrets and offer previous entries
+/// from a `History` as suggestions
+pub struct EntryBox<'a> {
+    pub initial: Option<String>,
+    pub placeholder: Option<String>,
+    pub password: bool,
+    pub history: Option<History>,
+    pub window: Window<'a>
+}
+
+impl<'a> EntryBox<'a> {
+    /// create a new empty `EntryBox`
+    pub fn new() -> Self {
+        EntryBox {
+            initial: None,
+            placeholder: None,
+            password: false,
+            history: None,
+            window: EntryBox::create_window()
+        }
+    }
 
-impl<'a> EntryBox {
     /// create a rofi window with 0 lines. This is important as it simulates a text entry field
     pub fn create_window() -> Window<'a> {
         Window::new("EntryBox").lines(0).format('s')
     }
 
+    /// prefill the entry field with `text`, useful when editing an existing value
+    pub fn initial(mut self, text: String) -> Self {
+        self.initial = Some(text);
+        self
+    }
+
+    /// show `text` in the empty entry field
+    pub fn placeholder(mut self, text: String) -> Self {
+        self.placeholder = Some(text);
+        self
+    }
+
+    /// hide the typed input, for passwords and other secrets. Input is never added to the history
+    pub fn password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// offer the entries of `history` as suggestions and record accepted input in it. Return
+    /// accepts the highlighted suggestion, so input that is part of an earlier entry ("10" with
+    /// "100" in the history) has to be entered with rofi's `kb-accept-custom` binding
+    /// (Control+Return by default) to be taken as typed
+    pub fn history(mut self, history: History) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// set a completely custom window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
     /// run the constructed rofi window and return the user input as a string wrapped in a
     /// `RustofiResult::Selection`
-    pub fn display(prompt: String) -> RustofiResult {
-        let result = EntryBox::create_window()
+    pub fn display(&mut self, prompt: String) -> RustofiResult {
+        self.validate(prompt, |_| Ok(()))
+    }
+
+    /// run the rofi window until the input passes `validator`. Each time validation fails the
+    /// window is reopened with the error in its message and the rejected input still entered.
+    /// Valid input is returned wrapped in a `RustofiResult::Selection`
+    pub fn validate<V: FnMut(&String) -> CallbackResult>(
+        &mut self, prompt: String, mut validator: V
+    ) -> RustofiResult {
+        match self.prompt_until(prompt, |input| validator(input).map(|_| input.clone())) {
+            Ok(Some(input)) => RustofiResult::Selection(input),
+            Ok(None) => RustofiResult::Cancel,
+            Err(m) => RustofiResult::Error(m)
+        }
+    }
+
+    /// run the rofi window until the input parses into a `T`, reopening it with the parse error
+    /// shown otherwise. Returns `Ok(None)` if the user cancels
+    pub fn parse<T: FromStr>(&mut self, prompt: String) -> Result<Option<T>, String>
+    where
+        T::Err: Display
+    {
+        self.parse_with(prompt, |_: &T| Ok(()))
+    }
+
+    /// like `parse`, but the parsed value must also pass `validator` before it is returned
+    pub fn parse_with<T: FromStr, V: FnMut(&T) -> CallbackResult>(
+        &mut self, prompt: String, mut validator: V
+    ) -> Result<Option<T>, String>
+    where
+        T::Err: Display
+    {
+        self.prompt_until(prompt, |input| {
+            let value = input.parse::<T>().map_err(|e| e.to_string())?;
+            validator(&value)?;
+            Ok(value)
+        })
+    }
+
+    /// run the rofi window until `convert` turns the input into a `T`, reopening it with the
+    /// error `convert` returned otherwise. Returns `Ok(None)` if the user cancels
+    pub fn convert<T, F: FnMut(&String) -> Result<T, String>>(
+        &mut self, prompt: String, convert: F
+    ) -> Result<Option<T>, String> {
+        self.prompt_until(prompt, convert)
+    }
+
+    /// build the rofi window for one attempt, prefilled with `filter` and showing `error`
+    fn build_window(
+        &self, prompt: String, filter: Option<&String>, error: Option<&String>
+    ) -> Window<'a> {
+        let mut window = self.window.clone().prompt(prompt);
+        if let Some(e) = error {
+            let message = format!("<span color=\"#e01b24\">{}</span>", escape_markup(e));
+            window = window.message(message);
+        }
+        if let Some(text) = filter {
+            window = window.add_args(vec!["-filter".to_string(), text.clone()]);
+        }
+        if let Some(text) = &self.placeholder {
+            window = window.add_args(placeholder_args(text));
+        }
+        if self.password {
+            window = window.add_args(vec!["-password".to_string()]);
+        }
+        window
+    }
+
+    /// show the window until `convert` accepts the input, displaying its errors in the message
+    fn prompt_until<T, F: FnMut(&String) -> Result<T, String>>(
+        &mut self, prompt: String, mut convert: F
+    ) -> Result<Option<T>, String> {
+        let suggestions = match (&self.history, self.password) {
+            (Some(history), false) => history.entries(),
+            _ => Vec::new()
+        };
+        let mut filter = self.initial.clone();
+        let mut error = None;
+        loop {
+            let input = match self
+                .build_window(prompt.clone(), filter.as_ref(), error.as_ref())
+                .lines(suggestions.len().min(10) as i32)
+                .show(&suggestions)
+            {
+                Ok(input) => input,
+                Err(_) => return Err("error getting user input from rofi".to_string())
+            };
+            if input.is_empty() {
+                return Ok(None);
+            }
+            match convert(&input) {
+                Ok(value) => {
+                    if let (Some(history), false) = (&self.history, self.password) {
+                        history
+                            .push(&input)
+                            .map_err(|e| format!("error saving entry history: {}", e))?;
+                    }
+                    return Ok(Some(value));
+                }
+                Err(e) => {
+                    error = Some(e);
+                    // never echo a secret back onto rofi's command line
+                    filter = if self.password { None } else { Some(input) };
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Default for EntryBox<'a> {
+    fn default() -> Self {
+        EntryBox::new()
+    }
+}
+
+/// rofi args showing `text` in the empty entry field
+fn placeholder_args(text: &str) -> Vec<String> {
+    let text = text.replace('\\', "\\\\").replace('"', "\\\"");
+    vec![
+        "-theme-str".to_string(),
+        format!("entry {{ placeholder: \"{}\"; }}", text),
+    ]
+}
+
+/// returns the suggestions for the text typed so far
+pub type SuggestionCallback = Box<dyn FnMut(&str) -> Result<Vec<String>, String>>;
+
+/// where an `AutocompleteEntry` gets its suggestions from
+pub enum Suggestions {
+    /// a fixed list, filtered by rofi as the user types
+    Static(Vec<String>),
+    /// asked for the suggestions of the typed text when the window opens and whenever the
+    /// refresh key is pressed
+    Dynamic(SuggestionCallback)
+}
+
+/// what the user entered in an `AutocompleteEntry`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Completion {
+    /// the suggestion at `index` of the suggestions shown was picked
+    Suggestion { index: usize, text: String },
+    /// text matching no suggestion was entered
+    Text(String)
+}
+
+impl Completion {
+    /// the text entered, whether it was suggested or not
+    pub fn text(&self) -> &str {
+        match self {
+            Completion::Suggestion { text, .. } => text,
+            Completion::Text(text) => text
+        }
+    }
+}
+
+/// `AutocompleteEntry` is an entry field with suggestions listed below it. The user either picks a
+/// suggestion or enters any other text, and `display` tells which one it was
+pub struct AutocompleteEntry<'a> {
+    pub suggestions: Suggestions,
+    /// rofi key combination asking `Suggestions::Dynamic` for the suggestions of the typed text
+    pub refresh_key: String,
+    pub initial: Option<String>,
+    pub placeholder: Option<String>,
+    /// maximum number of suggestions visible at once
+    pub max_lines: usize,
+    pub window: Window<'a>
+}
+
+impl<'a> AutocompleteEntry<'a> {
+    /// create an entry suggesting a fixed list of `suggestions`
+    pub fn new(suggestions: Vec<String>) -> Self {
+        AutocompleteEntry::with_suggestions(Suggestions::Static(suggestions))
+    }
+
+    /// create an entry asking `callback` for suggestions of the typed text, when the window opens
+    /// and whenever the refresh key is pressed
+    pub fn dynamic(callback: SuggestionCallback) -> Self {
+        AutocompleteEntry::with_suggestions(Suggestions::Dynamic(callback))
+    }
+
+    fn with_suggestions(suggestions: Suggestions) -> Self {
+        AutocompleteEntry {
+            suggestions,
+            refresh_key: "Alt+Return".to_string(),
+            initial: None,
+            placeholder: None,
+            max_lines: 10,
+            window: AutocompleteEntry::create_window()
+        }
+    }
+
+    /// create a rofi window in the middle of the screen
+    fn create_window() -> Window<'a> {
+        Window::new("AutocompleteEntry")
+            .format('s')
+            .location(Location::MiddleCentre)
+    }
+
+    /// set the key combination refreshing dynamic suggestions, `Alt+Return` by default.
+    /// Keys rofi binds already, like `Control+space` for `kb-row-select`, conflict with it
+    pub fn refresh_key(mut self, key: &str) -> Self {
+        self.refresh_key = key.to_string();
+        self
+    }
+
+    /// prefill the entry field with `text`
+    pub fn initial(mut self, text: String) -> Self {
+        self.initial = Some(text);
+        self
+    }
+
+    /// show `text` in the empty entry field
+    pub fn placeholder(mut self, text: String) -> Self {
+        self.placeholder = Some(text);
+        self
+    }
+
+    /// set the maximum number of suggestions visible at once
+    pub fn max_lines(mut self, lines: usize) -> Self {
+        self.max_lines = lines;
+        self
+    }
+
+    /// set a completely custom window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// run the rofi window and return the suggestion picked or the text entered. Returns
+    /// `Ok(None)` if the user cancels
+    pub fn display(&mut self, prompt: String) -> Result<Option<Completion>, String> {
+        let mut typed = self.initial.clone().unwrap_or_default();
+        loop {
+            let suggestions = match &mut self.suggestions {
+                Suggestions::Static(suggestions) => suggestions.clone(),
+                Suggestions::Dynamic(callback) => guarded(|| callback(&typed))?
+            };
+            let mut window = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .lines(suggestions.len().min(self.max_lines) as i32);
+            if !typed.is_empty() {
+                window = window.add_args(vec!["-filter".to_string(), typed.clone()]);
+            }
+            if let Some(text) = &self.placeholder {
+                window = window.add_args(placeholder_args(text));
+            }
+            if let Suggestions::Dynamic(_) = self.suggestions {
+                let refresh = vec!["-kb-custom-1".to_string(), self.refresh_key.clone()];
+                window = window.add_args(refresh);
+            }
+            return match window.select(&suggestions) {
+                Ok(Response::Row(index)) => Ok(Some(Completion::Suggestion {
+                    index,
+                    text: suggestions[index].clone()
+                })),
+                Ok(Response::Entry(text)) => Ok(Some(Completion::Text(text))),
+                Ok(Response::Key { filter, .. }) => {
+                    typed = filter;
+                    continue;
+                }
+                Ok(Response::Cancel) => Ok(None),
+                Err(_) => Err("error getting user input from rofi".to_string())
+            };
+        }
+    }
+}
+
+/// callback rendering the current value of a `Field`
+pub type FieldDisplay<T> = Box<dyn Fn(&T) -> String>;
+/// callback letting the user edit a `Field` of the given value, the label is passed as prompt
+pub type FieldEdit<T> = Box<dyn FnMut(&mut T, &String) -> CallbackResult>;
+
+/// a single labeled row of a `Form`, reading its value from and writing it back to a `T`
+pub struct Field<T> {
+    pub label: String,
+    pub display: FieldDisplay<T>,
+    pub edit: FieldEdit<T>
+}
+
+impl<T: 'static> Field<T> {
+    /// create a field with completely custom rendering and editing. `edit` should leave the value
+    /// untouched when the user cancels and only return an error if something actually failed
+    pub fn custom(label: &str, display: FieldDisplay<T>, edit: FieldEdit<T>) -> Self {
+        Field {
+            label: label.to_string(),
+            display,
+            edit
+        }
+    }
+
+    /// a free text field edited with an `EntryBox` prefilled with the current value
+    pub fn text<G, S>(label: &str, get: G, set: S) -> Self
+    where
+        G: 'static + Fn(&T) -> String,
+        S: 'static + Fn(&mut T, String)
+    {
+        let get = Rc::new(get);
+        let current = get.clone();
+        Field::custom(
+            label,
+            Box::new(move |t| escape_markup(&get(t))),
+            Box::new(move |t, prompt| {
+                match EntryBox::new().initial(current(t)).display(prompt.clone()) {
+                    RustofiResult::Selection(s) => set(t, s),
+                    RustofiResult::Error(m) => return Err(m),
+                    _ => {}
+                };
+                Ok(())
+            })
+        )
+    }
+
+    /// a field holding a number, or any other type parsed from text. Input that doesn't parse is
+    /// rejected and the `EntryBox` reopened with the parse error
+    pub fn parsed<N, G, S>(label: &str, get: G, set: S) -> Self
+    where
+        N: FromStr + Display,
+        N::Err: Display,
+        G: 'static + Fn(&T) -> N,
+        S: 'static + Fn(&mut T, N)
+    {
+        let get = Rc::new(get);
+        let current = get.clone();
+        Field::custom(
+            label,
+            Box::new(move |t| escape_markup(&get(t).to_string())),
+            Box::new(move |t, prompt| {
+                let mut entry = EntryBox::new().initial(current(t).to_string());
+                if let Some(value) = entry.parse::<N>(prompt.clone())? {
+                    set(t, value);
+                }
+                Ok(())
+            })
+        )
+    }
+
+    /// a boolean field, selecting it flips the value without opening another window
+    pub fn toggle<G, S>(label: &str, get: G, set: S) -> Self
+    where
+        G: 'static + Fn(&T) -> bool,
+        S: 'static + Fn(&mut T, bool)
+    {
+        let get = Rc::new(get);
+        let current = get.clone();
+        Field::custom(
+            label,
+            Box::new(move |t| if get(t) { "yes" } else { "no" }.to_string()),
+            Box::new(move |t, _| {
+                let value = current(t);
+                set(t, !value);
+                Ok(())
+            })
+        )
+    }
+
+    /// a field holding one of `options`, picked from a list with the current value highlighted
+    pub fn choice<G, S>(label: &str, options: Vec<String>, get: G, set: S) -> Self
+    where
+        G: 'static + Fn(&T) -> String,
+        S: 'static + Fn(&mut T, String)
+    {
+        let get = Rc::new(get);
+        let current = get.clone();
+        Field::custom(
+            label,
+            Box::new(move |t| escape_markup(&get(t))),
+            Box::new(move |t, prompt| {
+                let value = current(t);
+                let selected_row = options.iter().position(|o| *o == value).unwrap_or(0);
+                let response = Window::new("Choice")
+                    .format('s')
+                    .location(Location::MiddleCentre)
+                    .prompt(prompt.clone())
+                    .lines(options.len() as i32)
+                    .add_args(vec!["-selected-row".to_string(), selected_row.to_string()])
+                    .show(options.iter());
+                match response {
+                    Ok(input) => {
+                        if options.contains(&input) {
+                            set(t, input);
+                        }
+                        Ok(())
+                    }
+                    Err(_) => Err("error getting user input from rofi".to_string())
+                }
+            })
+        )
+    }
+}
+
+/// `Form` collects several values into a struct `T`. Every field is listed with its current value,
+/// selecting one edits it and `[submit]` returns the filled struct once it passes validation.
+/// `[cancel]` or closing the window discards all edits
+pub struct Form<'a, T> {
+    pub value: T,
+    pub fields: Vec<Field<T>>,
+    pub validator: Box<dyn Fn(&T) -> CallbackResult>,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a, T: Clone> Form<'a, T> {
+    /// create a new `Form` editing `value` through `fields`
+    pub fn new(value: T, fields: Vec<Field<T>>) -> Self {
+        Form {
+            value,
+            fields,
+            validator: Box::new(|_| Ok(())),
+            labels: None,
+            window: Form::<T>::create_window()
+        }
+    }
+
+    /// create a simple rofi instance in the middle of the screen rendering markup rows
+    fn create_window() -> Window<'a> {
+        Window::new("Form")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string()])
+    }
+
+    /// set the check the filled struct must pass before it can be submitted
+    pub fn validate(mut self, validator: Box<dyn Fn(&T) -> CallbackResult>) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// set a completely custom rofi window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// run the form until it is submitted or cancelled. On submit the filled struct is returned
+    /// and kept as the form's `value`, cancelling returns `Ok(None)` and leaves `value` untouched
+    pub fn display(&mut self, prompt: String) -> Result<Option<T>, String> {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let mut working = self.value.clone();
+        let mut selected_row = 0;
+        let mut error: Option<String> = None;
+        loop {
+            let mut display_options: Vec<String> = self
+                .fields
+                .iter()
+                .map(|f| format!("<b>{}</b>: {}", escape_markup(&f.label), (f.display)(&working)))
+                .collect();
+            let extra = labels.rows(&[BuiltIn::Separator, BuiltIn::Submit, BuiltIn::Cancel]);
+            let first_extra = display_options.len();
+            display_options.extend(extra.iter().map(|(_, label)| label.clone()));
+            let mut window = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .lines(display_options.len() as i32)
+                .add_args(vec!["-selected-row".to_string(), selected_row.to_string()]);
+            if let Some(e) = error.take() {
+                let message = format!("<span color=\"#e01b24\">{}</span>", escape_markup(&e));
+                window = window.message(message);
+            }
+            let row = match window.select(display_options) {
+                Ok(Response::Row(row)) => row,
+                Ok(Response::Cancel) => return Ok(None),
+                // custom input matching no row
+                Ok(_) => continue,
+                Err(_) => return Err("error getting user input from rofi".to_string())
+            };
+            if row < self.fields.len() {
+                let field = &mut self.fields[row];
+                guarded(|| (field.edit)(&mut working, &field.label))?;
+                selected_row = row;
+                continue;
+            }
+            match extra.get(row - first_extra).map(|(b, _)| *b) {
+                Some(BuiltIn::Submit) => match guarded(|| (self.validator)(&working)) {
+                    Ok(_) => {
+                        self.value = working.clone();
+                        return Ok(Some(working));
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        selected_row = row;
+                    }
+                },
+                Some(BuiltIn::Cancel) => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `Checklist` toggles many items on and off in a single window. Each item is shown with a marker
+/// for its state, selecting it flips the state and the window opens again on the same row.
+/// `[done]` returns the checked items, `[cancel]` or closing the window discards the changes
+pub struct Checklist<'a, T> {
+    pub items: Vec<T>,
+    pub checked: Vec<bool>,
+    /// text shown before checked and unchecked items
+    pub markers: (String, String),
+    /// icons shown next to checked and unchecked items, replacing the markers
+    pub icons: Option<(String, String)>,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a, T: Display + Clone> Checklist<'a, T> {
+    /// create a checklist of `items`, all of them unchecked
+    pub fn new(items: Vec<T>) -> Self {
+        let checked = vec![false; items.len()];
+        Checklist {
+            items,
+            checked,
+            markers: ("[x]".to_string(), "[ ]".to_string()),
+            icons: None,
+            labels: None,
+            window: Checklist::<T>::create_window()
+        }
+    }
+
+    /// create a simple rofi instance in the middle of the screen rendering markup rows
+    fn create_window() -> Window<'a> {
+        Window::new("Checklist")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string()])
+    }
+
+    /// check the items `is_checked` returns `true` for
+    pub fn checked<F: Fn(&T) -> bool>(mut self, is_checked: F) -> Self {
+        self.checked = self.items.iter().map(is_checked).collect();
+        self
+    }
+
+    /// set the text shown before checked and unchecked items
+    pub fn markers(mut self, checked: &str, unchecked: &str) -> Self {
+        self.markers = (checked.to_string(), unchecked.to_string());
+        self
+    }
+
+    /// show icons next to checked and unchecked items instead of markers
+    pub fn icons(mut self, checked: &str, unchecked: &str) -> Self {
+        self.icons = Some((checked.to_string(), unchecked.to_string()));
+        self
+    }
+
+    /// set a completely custom rofi window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// the row showing `item` in the given state
+    fn row(&self, item: &T, checked: bool) -> String {
+        match (&self.icons, checked) {
+            (Some((icon, _)), true) | (Some((_, icon)), false) => {
+                Row::new(&item.to_string()).icon(icon).to_string()
+            }
+            (None, true) => format!("{} {}", self.markers.0, item),
+            (None, false) => format!("{} {}", self.markers.1, item)
+        }
+    }
+
+    /// the checked items in order
+    fn checked_items(&self) -> Vec<T> {
+        let items = self.items.iter().zip(&self.checked);
+        items.filter(|(_, c)| **c).map(|(i, _)| i.clone()).collect()
+    }
+
+    /// show the checklist until `[done]` is selected, returning the checked items in order.
+    /// Returns `None` if the user cancelled. Items added without a state start unchecked
+    pub fn display(&mut self, prompt: String) -> Result<Option<Vec<T>>, String> {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let extra = labels.rows(&[BuiltIn::Separator, BuiltIn::Done, BuiltIn::Cancel]);
+        self.checked.resize(self.items.len(), false);
+        let mut checked = self.checked.clone();
+        let mut selected_row = 0;
+        loop {
+            let mut display_options: Vec<String> = self
+                .items
+                .iter()
+                .zip(&checked)
+                .map(|(item, checked)| self.row(item, *checked))
+                .collect();
+            display_options.extend(extra.iter().map(|(_, label)| label.clone()));
+            let mut window = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .lines(display_options.len() as i32)
+                .add_args(vec!["-selected-row".to_string(), selected_row.to_string()]);
+            if self.icons.is_some() {
+                window = window.add_args(vec!["-show-icons".to_string()]);
+            }
+            let row = match window.select(display_options) {
+                Ok(Response::Row(row)) => row,
+                Ok(Response::Cancel) => return Ok(None),
+                // custom input matching no row
+                Ok(_) => continue,
+                Err(_) => return Err("error getting user input from rofi".to_string())
+            };
+            selected_row = row;
+            match toggle(&mut checked, &extra, row) {
+                Some(BuiltIn::Done) => {
+                    self.checked = checked;
+                    return Ok(Some(self.checked_items()));
+                }
+                Some(BuiltIn::Cancel) => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// flip the item on `row` of a checklist, or give the built-in row below the items it is
+fn toggle(checked: &mut [bool], extra: &[(BuiltIn, String)], row: usize) -> Option<BuiltIn> {
+    match checked.get_mut(row) {
+        Some(state) => {
+            *state = !*state;
+            None
+        }
+        None => extra.get(row - checked.len()).map(|(b, _)| *b)
+    }
+}
+
+/// callback extracting the text of a `Column` from a row, may contain pango markup
+pub type ColumnValue<T> = Box<dyn Fn(&T) -> String>;
+
+/// a column of a `Table`, its text taken from every row by an extractor closure
+pub struct Column<T> {
+    /// title shown in the header row
+    pub title: String,
+    /// text of the column for a row
+    pub value: ColumnValue<T>,
+    /// width the column's text is cut down to, with an ellipsis
+    pub max_width: Option<usize>
+}
+
+impl<T> Column<T> {
+    /// create a column titled `title` showing `value` for every row
+    pub fn new(title: &str, value: ColumnValue<T>) -> Self {
+        Column {
+            title: title.to_string(),
+            value,
+            max_width: None
+        }
+    }
+
+    /// cut text longer than `width` characters, ending it with an ellipsis
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+}
+
+/// `Table` lists records of type `T` with one aligned column per `Column`. Widths are computed
+/// from the widest cell of each column, ignoring pango markup, so the window should use a
+/// monospace font to line them up. Columns are separated with rofi's `-display-column-separator`
+/// and only the columns given to `search_columns` are shown with `-display-columns`, which is also
+/// what rofi matches the filter against. The selected row is handed back to the callback as `T`
+pub struct Table<'a, T> {
+    pub rows: Vec<T>,
+    pub columns: Vec<Column<T>>,
+    pub row_callback: Box<dyn RustofiCallback<T>>,
+    /// indices of the columns shown and searched, every column when empty
+    pub search_columns: Vec<usize>,
+    /// whether a non-selectable row with the column titles is shown first
+    pub header: bool,
+    pub separator: String,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a, T> Table<'a, T> {
+    /// create a table of `rows` with the given columns, running `row_callback` on the selected row
+    pub fn new(
+        rows: Vec<T>, columns: Vec<Column<T>>, row_callback: Box<dyn RustofiCallback<T>>
+    ) -> Self {
+        Table {
+            rows,
+            columns,
+            row_callback,
+            search_columns: Vec::new(),
+            header: true,
+            separator: "\t".to_string(),
+            labels: None,
+            window: Table::<T>::create_window()
+        }
+    }
+
+    /// create a rofi window in the middle of the screen rendering markup
+    fn create_window() -> Window<'a> {
+        Window::new("Table")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string()])
+    }
+
+    /// show and search only the columns at these indices, in table order
+    pub fn search_columns(mut self, columns: Vec<usize>) -> Self {
+        self.search_columns = columns;
+        self
+    }
+
+    /// show or hide the row of column titles
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// set the text separating columns, a tab by default. rofi reads it as a regex
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// set a completely custom window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// the cells of every row, truncated and padded to the width of their column
+    fn cells(&self) -> Vec<Vec<String>> {
+        let mut cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let values = self.columns.iter().map(|c| match c.max_width {
+                    Some(width) => truncate_markup(&(c.value)(row), width),
+                    None => (c.value)(row)
+                });
+                values.collect()
+            })
+            .collect();
+        if self.header {
+            let titles = self.columns.iter().map(|c| format!("<b>{}</b>", escape_markup(&c.title)));
+            cells.insert(0, titles.collect());
+        }
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|c| cells.iter().map(|row| markup_len(&row[c])).max().unwrap_or(0))
+            .collect();
+        for row in cells.iter_mut() {
+            for (cell, width) in row.iter_mut().zip(&widths) {
+                let padding = width - markup_len(cell);
+                cell.push_str(&" ".repeat(padding));
+            }
+        }
+        cells
+    }
+
+    /// show the table, run the callback with the selected row and return the text of its first
+    /// column, or return custom input as is
+    pub fn display(&mut self, prompt: String) -> RustofiResult {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let extra = labels.rows(&[BuiltIn::Separator, BuiltIn::Cancel]);
+        let first_row = self.header as usize;
+        let cells = self.cells();
+        let mut display_options: Vec<String> = Vec::new();
+        for (i, row) in cells.iter().enumerate() {
+            let line = row.join(&self.separator);
+            display_options.push(Row::new(&line).nonselectable(i < first_row).to_string());
+        }
+        display_options.extend(extra.into_iter().map(|(_, label)| label));
+        let mut args = vec!["-display-column-separator".to_string(), self.separator.clone()];
+        if !self.search_columns.is_empty() {
+            let columns: Vec<String> = self.search_columns.iter().map(|c| (c + 1).to_string()).collect();
+            args.push("-display-columns".to_string());
+            args.push(columns.join(","));
+        }
+        let response = self
+            .window
+            .clone()
+            .lines(display_options.len() as i32)
             .prompt(prompt)
-            .show(vec!["".to_string()]);
-        match result {
-            Ok(input) => {
-                if input == "" {
-                    RustofiResult::Cancel
-                } else {
-                    RustofiResult::Selection(input)
+            .add_args(args)
+            .select(display_options);
+        match response {
+            Ok(Response::Row(row)) if row >= first_row && row < first_row + self.rows.len() => {
+                let (item, callback) = (&mut self.rows[row - first_row], &mut self.row_callback);
+                match guarded(|| callback(item)) {
+                    Ok(_) => match self.columns.first() {
+                        Some(column) => RustofiResult::Selection((column.value)(item)),
+                        None => RustofiResult::Success
+                    },
+                    Err(m) => RustofiResult::Error(m)
+                }
+            }
+            Ok(Response::Entry(input)) => RustofiResult::Selection(input),
+            Ok(_) => RustofiResult::Cancel,
+            Err(_) => RustofiResult::Error("error getting user input from rofi".to_string())
+        }
+    }
+}
+
+/// number of characters pango shows for `text`, tags left out and entities counted once
+fn markup_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                chars.by_ref().find(|c| *c == '>');
+            }
+            '&' => {
+                chars.by_ref().find(|c| *c == ';');
+                len += 1;
+            }
+            _ => len += 1
+        }
+    }
+    len
+}
+
+/// cut the markup `text` down to `width` shown characters ending with an ellipsis, keeping tags
+/// and entities whole and closing the tags left open
+fn truncate_markup(text: &str, width: usize) -> String {
+    if markup_len(text) <= width || width == 0 {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut open: Vec<String> = Vec::new();
+    let mut len = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                let tag: String = chars.by_ref().take_while(|c| *c != '>').collect();
+                if let Some(name) = tag.strip_prefix('/') {
+                    // closes the innermost tag of that name, outer ones stay open
+                    if let Some(i) = open.iter().rposition(|t| t == name.trim()) {
+                        open.remove(i);
+                    }
+                } else if !tag.ends_with('/') {
+                    open.push(tag.split_whitespace().next().unwrap_or("").to_string());
                 }
+                result.push_str(&format!("<{}>", tag));
+            }
+            _ if len + 1 >= width => break,
+            '&' => {
+                let entity: String = chars.by_ref().take_while(|c| *c != ';').collect();
+                result.push_str(&format!("&{};", entity));
+                len += 1;
             }
+            _ => {
+                result.push(c);
+                len += 1;
+            }
+        }
+    }
+    result.push('…');
+    for tag in open.iter().rev() {
+        result.push_str(&format!("</{}>", tag));
+    }
+    result
+}
+
+/// severity of a `MessageBox`, decides the title shown above the message
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageKind {
+    /// a purely informational message
+    Info,
+    /// something the user should be made aware of
+    Warning,
+    /// something went wrong
+    Error
+}
+
+impl MessageKind {
+    /// pango markup title shown above the message
+    fn title(&self) -> &'static str {
+        match self {
+            MessageKind::Info => "<b>Info</b>",
+            MessageKind::Warning => "<span color=\"#e5a50a\"><b>Warning</b></span>",
+            MessageKind::Error => "<span color=\"#e01b24\"><b>Error</b></span>"
+        }
+    }
+}
+
+/// buttons a `MessageBox` can offer beneath its message
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageButton {
+    /// acknowledge the message
+    Ok,
+    /// ask the caller to try the failed operation again
+    Retry
+}
+
+impl Display for MessageButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MessageButton::Ok => write!(f, "{}", Labels::global().ok),
+            MessageButton::Retry => write!(f, "{}", Labels::global().retry)
+        }
+    }
+}
+
+/// `MessageBox` shows an informational, warning or error message to the user. The message may
+/// contain pango markup. Without buttons it is a plain rofi `-e` window, otherwise the message is
+/// shown above a list of the given buttons
+pub struct MessageBox<'a> {
+    pub kind: MessageKind,
+    pub buttons: Vec<MessageButton>,
+    pub window: Window<'a>
+}
+
+impl<'a> MessageBox<'a> {
+    /// create a new `MessageBox` of the given kind without any buttons
+    pub fn new(kind: MessageKind) -> Self {
+        MessageBox {
+            kind,
+            buttons: Vec::new(),
+            window: MessageBox::create_window()
+        }
+    }
+
+    /// create a new informational `MessageBox`
+    pub fn info() -> Self {
+        MessageBox::new(MessageKind::Info)
+    }
+
+    /// create a new warning `MessageBox`
+    pub fn warning() -> Self {
+        MessageBox::new(MessageKind::Warning)
+    }
+
+    /// create a new error `MessageBox`
+    pub fn error() -> Self {
+        MessageBox::new(MessageKind::Error)
+    }
+
+    /// create a simple rofi instance in the middle of the screen that renders pango markup
+    fn create_window() -> Window<'a> {
+        Window::new("MessageBox")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup".to_string()])
+    }
+
+    /// set the buttons shown beneath the message
+    pub fn buttons(mut self, buttons: Vec<MessageButton>) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// set a completely custom rofi window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// display the message and block until the user dismisses it. Dismissing the window or
+    /// selecting `OK` returns `RustofiResult::Success`, selecting `Retry` returns it as a
+    /// `RustofiResult::Action` and closing a window with buttons returns `RustofiResult::Cancel`
+    pub fn display(&mut self, message: String) -> RustofiResult<MessageButton> {
+        let text = format!("{}\n{}", self.kind.title(), message);
+        if self.buttons.is_empty() {
+            return match self.window.clone().show_error(text) {
+                Ok(_) => RustofiResult::Success,
+                Err(_) => RustofiResult::Error("error displaying message with rofi".to_string())
+            };
+        }
+        let display_options: Vec<String> = self.buttons.iter().map(|b| b.to_string()).collect();
+        let response = self
+            .window
+            .clone()
+            .message(text)
+            .lines(display_options.len() as i32)
+            .select(display_options);
+        match response {
+            Ok(Response::Row(row)) if self.buttons.get(row) == Some(&MessageButton::Retry) => {
+                RustofiResult::Action(MessageButton::Retry)
+            }
+            Ok(Response::Cancel) => RustofiResult::Cancel,
+            Ok(_) => RustofiResult::Success,
             Err(_) => RustofiResult::Error("error getting user input from rofi".to_string())
         }
     }
 }
+
+/// question asked before running an action that requires confirmation
+pub(crate) fn confirm_question(action: &str) -> String {
+    format!("Are you sure you want to {}?", action)
+}
+
+/// `Confirm` asks the user a yes/no question before something destructive happens. The answer
+/// is returned as a `bool`, closing the window always counts as a "no". In "type to confirm" mode
+/// the user has to type a given text (like the name of the thing being deleted) instead
+pub struct Confirm<'a> {
+    pub affirmative: String,
+    pub negative: String,
+    pub default: bool,
+    pub confirm_text: Option<String>,
+    pub window: Window<'a>
+}
+
+impl<'a> Confirm<'a> {
+    /// create a new `Confirm` with the global `yes` and `no` labels as answers, defaulting to `no`
+    pub fn new() -> Self {
+        let labels = Labels::global();
+        Confirm {
+            affirmative: labels.yes,
+            negative: labels.no,
+            default: false,
+            confirm_text: None,
+            window: Confirm::create_window()
+        }
+    }
+
+    /// create a simple rofi instance in the middle of the screen
+    fn create_window() -> Window<'a> {
+        Window::new("Confirm")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string()])
+    }
+
+    /// set the labels of the affirmative and negative answers
+    pub fn labels(mut self, affirmative: String, negative: String) -> Self {
+        self.affirmative = affirmative;
+        self.negative = negative;
+        self
+    }
+
+    /// set which answer is highlighted when the window opens
+    pub fn default_answer(mut self, default: bool) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// require the user to type `text` exactly to confirm instead of picking an answer
+    pub fn type_to_confirm(mut self, text: String) -> Self {
+        self.confirm_text = Some(text);
+        self
+    }
+
+    /// set a completely custom rofi window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// ask `question` and return whether the user confirmed it
+    pub fn display(&mut self, question: String) -> bool {
+        if let Some(text) = &self.confirm_text {
+            let response = self
+                .window
+                .clone()
+                .prompt(question)
+                .message(format!("Type <b>{}</b> to confirm", escape_markup(text)))
+                .lines(0)
+                .select(Vec::<String>::new());
+            return matches!(response, Ok(Response::Entry(input)) if &input == text);
+        }
+        let selected_row = if self.default { 0 } else { 1 };
+        // answers are told apart by position, so they can share the same label
+        let response = self
+            .window
+            .clone()
+            .prompt(question)
+            .lines(2)
+            .add_args(vec!["-selected-row".to_string(), selected_row.to_string()])
+            .select(vec![&self.affirmative, &self.negative]);
+        matches!(response, Ok(Response::Row(0)))
+    }
+}
+
+impl<'a> Default for Confirm<'a> {
+    fn default() -> Self {
+        Confirm::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markup_len_skips_tags_and_counts_entities_once() {
+        assert_eq!(markup_len("plain"), 5);
+        assert_eq!(markup_len("<b>bold</b>"), 4);
+        assert_eq!(markup_len("<span color=\"red\"><i>ab</i>c</span>"), 3);
+        assert_eq!(markup_len("a &amp; b"), 5);
+        assert_eq!(markup_len("&lt;&gt;"), 2);
+    }
+
+    #[test]
+    fn truncate_markup_leaves_short_text_alone() {
+        assert_eq!(truncate_markup("<b>abc</b>", 3), "<b>abc</b>");
+        // a width of 0 means no limit
+        assert_eq!(truncate_markup("abcdef", 0), "abcdef");
+    }
+
+    #[test]
+    fn truncate_markup_ends_with_an_ellipsis() {
+        assert_eq!(truncate_markup("abcdef", 4), "abc…");
+        assert_eq!(truncate_markup("abcdef", 1), "…");
+    }
+
+    #[test]
+    fn truncate_markup_keeps_entities_whole() {
+        assert_eq!(truncate_markup("a&amp;bcdef", 3), "a&amp;…");
+    }
+
+    #[test]
+    fn truncate_markup_closes_open_tags() {
+        assert_eq!(truncate_markup("<b>abcdef</b>", 3), "<b>ab…</b>");
+        assert_eq!(
+            truncate_markup("<span size=\"small\"><i>abcdef</i></span>", 3),
+            "<span size=\"small\"><i>ab…</i></span>"
+        );
+        // tags closed before the cut stay closed
+        assert_eq!(truncate_markup("<b>a</b>bcdef", 3), "<b>a</b>b…");
+    }
+
+    #[test]
+    fn truncate_markup_closes_nested_tags_of_the_same_name() {
+        assert_eq!(
+            truncate_markup("<span><span>a</span>bcdef</span>", 3),
+            "<span><span>a</span>b…</span>"
+        );
+        assert_eq!(truncate_markup("<b><b>abcdef</b></b>", 3), "<b><b>ab…</b></b>");
+    }
+
+    #[test]
+    fn checklist_rows_toggle_their_item() {
+        let extra = Labels::default().rows(&[BuiltIn::Separator, BuiltIn::Done, BuiltIn::Cancel]);
+        let mut checked = vec![false, true];
+        assert_eq!(toggle(&mut checked, &extra, 0), None);
+        assert_eq!(toggle(&mut checked, &extra, 1), None);
+        assert_eq!(checked, vec![true, false]);
+        assert_eq!(toggle(&mut checked, &extra, 0), None);
+        assert_eq!(checked, vec![false, false]);
+    }
+
+    #[test]
+    fn checklist_built_in_rows_follow_the_items() {
+        let extra = Labels::default().rows(&[BuiltIn::Separator, BuiltIn::Done, BuiltIn::Cancel]);
+        let mut checked = vec![true, false];
+        // the default labels have no separator
+        assert_eq!(toggle(&mut checked, &extra, 2), Some(BuiltIn::Done));
+        assert_eq!(toggle(&mut checked, &extra, 3), Some(BuiltIn::Cancel));
+        assert_eq!(toggle(&mut checked, &extra, 4), None);
+        assert_eq!(checked, vec![true, false]);
+    }
+
+    #[test]
+    fn checklist_returns_the_checked_items_in_order() {
+        let list = Checklist::new(vec!["a", "b", "c"]).checked(|i| *i != "b");
+        assert_eq!(list.checked_items(), vec!["a", "c"]);
+        let list = Checklist::new(vec!["a", "b"]).markers("+", "-").checked(|i| *i == "b");
+        assert_eq!(list.row(&"a", false), "- a");
+        assert_eq!(list.row(&"b", true), "+ b");
+    }
+}
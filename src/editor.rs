@@ -0,0 +1,426 @@
+//! A `ListEditor` is a complete add/edit/delete screen for a list kept in a `Store`. It shows the
+//! items with an `[add]` entry; selecting an item opens a menu to edit, move, delete it or run
+//! custom actions on it. Every change is saved to the store right away, and recorded in the
+//! `UndoStack` given with `undo` so it can be taken back.
+//!
+//! Lighter, an `InlineEdit` given to an `AppPage` or `ItemList` binds a key editing the
+//! highlighted item: an `EntryBox` opens prefilled with the item's editable text and what the user
+//! enters is converted back into an item replacing the old one, saved if the list has a store.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::components::EntryBox;
+//! use rustofi::editor::ListEditor;
+//! use rustofi::store::MemoryStore;
+//!
+//! let store = MemoryStore::new(vec!["milk".to_string(), "eggs".to_string()]);
+//! ListEditor::new(
+//!     store,
+//!     Box::new(|| EntryBox::new().parse::<String>("New item".to_string()))
+//! )
+//! .edit(Box::new(|item: &String| {
+//!     EntryBox::new()
+//!         .initial(item.clone())
+//!         .parse::<String>("Edit item".to_string())
+//! }))
+//! .action("[shout]", Box::new(|item: &mut String| {
+//!     *item = item.to_uppercase();
+//!     Ok(())
+//! }))
+//! .display("Groceries".to_string());
+//! ```
+//!
+//! ```no_run
+//! use rustofi::components::ItemList;
+//! use rustofi::editor::InlineEdit;
+//!
+//! // rename an item with Alt+e, keeping its `TODO`/`DONE` prefix
+//! let todos = vec!["TODO buy milk".to_string(), "DONE call mom".to_string()];
+//! ItemList::new(todos, Box::new(|_| Ok(())))
+//!     .edit(InlineEdit::new(
+//!         "Alt+e",
+//!         Box::new(|todo: &String| todo[5..].to_string()),
+//!         Box::new(|task: &str, todo: &String| match task.trim() {
+//!             "" => Err("the task can't be empty".to_string()),
+//!             task => Ok(format!("{} {}", &todo[..4], task))
+//!         })
+//!     ))
+//!     .display("Todos".to_string());
+//! ```
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::components::{Confirm, EntryBox};
+use crate::guard::guarded;
+use crate::labels::{BuiltIn, Labels};
+use crate::store::{Store, StoreLock};
+use crate::undo::{snapshot, undo_rows, Snapshot, UndoStack};
+use crate::window::{Location, Response, Window};
+use crate::{bound_to_key, keybinding_args, Bound, CallbackResult, RustofiResult};
+
+/// creates a new item, `None` when the user gave up
+pub type ItemConstructor<T> = Box<dyn FnMut() -> Result<Option<T>, String>>;
+/// returns an edited copy of an item, `None` when the user gave up
+pub type ItemEditor<T> = Box<dyn FnMut(&T) -> Result<Option<T>, String>>;
+/// custom action changing an item in place
+pub type ItemActionCallback<T> = Box<dyn FnMut(&mut T) -> CallbackResult>;
+
+/// the editable text of an item, prefilled in the entry
+pub type EditText<T> = Box<dyn Fn(&T) -> String>;
+/// turns the entered text back into an item, given the item being edited. An error is shown in
+/// the entry window, which stays open
+pub type EditParse<T> = Box<dyn FnMut(&str, &T) -> Result<T, String>>;
+
+/// editing of the highlighted item of an `AppPage` or `ItemList` through a prefilled `EntryBox`,
+/// opened by a keybinding
+pub struct InlineEdit<T> {
+    /// rofi key combination editing the highlighted item, like `Alt+e`
+    pub keybinding: String,
+    pub text: EditText<T>,
+    pub parse: EditParse<T>,
+    /// prompt of the entry window
+    pub prompt: String
+}
+
+impl<T> InlineEdit<T> {
+    /// edit the highlighted item with `keybinding`, prefilling `text` of it and building the
+    /// edited item with `parse`
+    pub fn new(keybinding: &str, text: EditText<T>, parse: EditParse<T>) -> Self {
+        InlineEdit {
+            keybinding: keybinding.to_string(),
+            text,
+            parse,
+            prompt: "Edit".to_string()
+        }
+    }
+
+    /// set the prompt of the entry window
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompt = prompt.to_string();
+        self
+    }
+
+    /// show the entry prefilled with the text of `item` until it parses, `None` if the user gave
+    /// up
+    pub(crate) fn edit(&mut self, item: &T) -> Result<Option<T>, String> {
+        let parse = &mut self.parse;
+        EntryBox::new()
+            .initial((self.text)(item))
+            .convert(self.prompt.clone(), |input| guarded(|| parse(input, item)))
+    }
+}
+
+impl<T: Display + FromStr + 'static> InlineEdit<T>
+where
+    T::Err: Display
+{
+    /// edit items as their `to_string()`, parsing the entry back with `FromStr`
+    pub fn parsed(keybinding: &str) -> Self {
+        InlineEdit::new(
+            keybinding,
+            Box::new(|item: &T| item.to_string()),
+            Box::new(|input: &str, _: &T| input.parse::<T>().map_err(|e| e.to_string()))
+        )
+    }
+}
+
+/// edit the item at `index` of `items` with `edit`, replacing it. Returns the label of the change
+/// if there was one, for the undo history
+pub(crate) fn edit_item<T: Display>(
+    edit: &mut Option<InlineEdit<T>>, items: &mut [T], index: usize
+) -> Result<Option<String>, String> {
+    let edit = match edit {
+        Some(edit) => edit,
+        None => return Ok(None)
+    };
+    match edit.edit(&items[index])? {
+        Some(edited) => {
+            let label = format!("edit {}", items[index]);
+            items[index] = edited;
+            Ok(Some(label))
+        }
+        None => Ok(None)
+    }
+}
+
+/// an entry of the menu opened on an item
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemOp {
+    Edit,
+    Custom(usize),
+    MoveUp,
+    MoveDown,
+    Delete
+}
+
+/// `ListEditor` drives the windows needed to add, edit, delete, reorder and run custom actions
+/// on the items of a `Store`
+pub struct ListEditor<'a, T, S> {
+    /// the store, shared with the undo operations recording changes to it
+    pub store: Rc<RefCell<S>>,
+    pub create: ItemConstructor<T>,
+    pub edit: Option<ItemEditor<T>>,
+    /// custom actions offered on every item, with their labels
+    pub actions: Vec<(String, ItemActionCallback<T>)>,
+    /// whether deleting an item asks for confirmation first
+    pub confirm_delete: bool,
+    /// whether items can be moved up and down
+    pub reorder: bool,
+    pub undo: Option<UndoStack>,
+    /// builds the operations recorded in `undo`
+    snapshot: Option<Snapshot<T>>,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a, T: Display + Clone, S: Store<T>> ListEditor<'a, T, S> {
+    /// create an editor of the list in `store`, building new items with `create`
+    pub fn new(store: S, create: ItemConstructor<T>) -> Self {
+        ListEditor {
+            store: Rc::new(RefCell::new(store)),
+            create,
+            edit: None,
+            actions: Vec::new(),
+            confirm_delete: true,
+            reorder: true,
+            undo: None,
+            snapshot: None,
+            labels: None,
+            window: ListEditor::<T, S>::create_window()
+        }
+    }
+
+    /// create a simple rofi instance in the middle of the screen rendering markup rows
+    fn create_window() -> Window<'a> {
+        Window::new("ListEditor")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string()])
+    }
+
+    /// offer `[edit]` on items, replacing them with what `edit` returns
+    pub fn edit(mut self, edit: ItemEditor<T>) -> Self {
+        self.edit = Some(edit);
+        self
+    }
+
+    /// offer a custom action labeled `label` on every item
+    pub fn action(mut self, label: &str, callback: ItemActionCallback<T>) -> Self {
+        self.actions.push((label.to_string(), callback));
+        self
+    }
+
+    /// ask for confirmation before deleting an item, on by default
+    pub fn confirm_delete(mut self, confirm: bool) -> Self {
+        self.confirm_delete = confirm;
+        self
+    }
+
+    /// offer `[move up]` and `[move down]` on items, on by default
+    pub fn reorder(mut self, reorder: bool) -> Self {
+        self.reorder = reorder;
+        self
+    }
+
+    /// record every change in `undo` and offer `[undo]` and `[redo]`, which take back the changes
+    /// recorded in it and show the list again
+    pub fn undo(mut self, undo: UndoStack) -> Self
+    where
+        T: 'static,
+        S: 'static
+    {
+        self.undo = Some(undo);
+        self.snapshot = Some(snapshot(&self.store));
+        self
+    }
+
+    /// set a completely custom window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// show the list until the user leaves it, returning `Cancel`. Failing to load or save the
+    /// list, or a failing callback, returns `Error`. The store stays locked meanwhile
+    pub fn display(&mut self, prompt: String) -> RustofiResult {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let _lock = match StoreLock::take(&self.store) {
+            Ok(lock) => lock,
+            Err(m) => return RustofiResult::Error(m)
+        };
+        let mut items = match self.store.borrow_mut().load() {
+            Ok(items) => items,
+            Err(m) => return RustofiResult::Error(m)
+        };
+        let mut selected_row = 0;
+        loop {
+            let mut extra = vec![BuiltIn::Add];
+            extra.extend(undo_rows(&self.undo));
+            extra.extend(&[BuiltIn::Separator, BuiltIn::Cancel]);
+            let extra = labels.rows(&extra);
+            let mut display_options: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+            display_options.extend(extra.iter().map(|(_, label)| label.clone()));
+            let undo = self.undo.as_ref();
+            let response = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .lines(display_options.len() as i32)
+                .add_args(vec!["-selected-row".to_string(), selected_row.to_string()])
+                .add_args(keybinding_args::<()>(None, &[], &[], undo))
+                .select(display_options);
+            let row = match response {
+                Ok(Response::Row(row)) => row,
+                Ok(Response::Key { key, .. }) => {
+                    let redo = match bound_to_key::<()>(None, &[], &[], undo, key) {
+                        Some(Bound::Redo) => true,
+                        Some(Bound::Undo) => false,
+                        _ => continue
+                    };
+                    match self.trigger_undo(redo) {
+                        Ok(reloaded) => items = reloaded,
+                        Err(m) => return RustofiResult::Error(m)
+                    }
+                    continue;
+                }
+                Ok(Response::Entry(_)) => continue,
+                Ok(_) => return RustofiResult::Cancel,
+                Err(_) => {
+                    return RustofiResult::Error("error getting user input from rofi".to_string())
+                }
+            };
+            let before = self.undo.as_ref().map(|_| items.clone());
+            let change = if row < items.len() {
+                selected_row = row;
+                match self.item_menu(&labels, &mut items, row) {
+                    Ok(Some((row, change))) => {
+                        selected_row = row;
+                        Some(change)
+                    }
+                    Ok(None) => None,
+                    Err(m) => return RustofiResult::Error(m)
+                }
+            } else {
+                match extra.get(row - items.len()).map(|(b, _)| *b) {
+                    Some(BuiltIn::Add) => match guarded(|| (self.create)()) {
+                        Ok(Some(item)) => {
+                            let change = format!("add {}", item);
+                            items.push(item);
+                            selected_row = items.len() - 1;
+                            Some(change)
+                        }
+                        Ok(None) => None,
+                        Err(m) => return RustofiResult::Error(m)
+                    },
+                    Some(built_in @ BuiltIn::Undo) | Some(built_in @ BuiltIn::Redo) => {
+                        match self.trigger_undo(built_in == BuiltIn::Redo) {
+                            Ok(reloaded) => items = reloaded,
+                            Err(m) => return RustofiResult::Error(m)
+                        }
+                        None
+                    }
+                    Some(BuiltIn::Cancel) => return RustofiResult::Cancel,
+                    _ => None
+                }
+            };
+            if let Some(change) = change {
+                if let Err(m) = self.store.borrow_mut().save(&items) {
+                    return RustofiResult::Error(m);
+                }
+                if let (Some(undo), Some(snapshot), Some(before)) =
+                    (&self.undo, &self.snapshot, before)
+                {
+                    undo.record(snapshot(&change, before, items.clone()));
+                }
+            }
+        }
+    }
+
+    /// undo or redo the latest change, returning the list as it's stored afterwards
+    fn trigger_undo(&mut self, redo: bool) -> Result<Vec<T>, String> {
+        if let Some(undo) = &self.undo {
+            match redo {
+                true => guarded(|| undo.redo())?,
+                false => guarded(|| undo.undo())?
+            };
+        }
+        self.store.borrow_mut().load()
+    }
+
+    /// show the menu of the item at `index` and apply the chosen operation. Returns the row the
+    /// item ends up on and the label of the change if the list changed
+    fn item_menu(
+        &mut self, labels: &Labels, items: &mut Vec<T>, index: usize
+    ) -> Result<Option<(usize, String)>, String> {
+        let mut ops = Vec::new();
+        if self.edit.is_some() {
+            ops.push((ItemOp::Edit, labels.edit.clone()));
+        }
+        for (i, (label, _)) in self.actions.iter().enumerate() {
+            ops.push((ItemOp::Custom(i), label.clone()));
+        }
+        if self.reorder && index > 0 {
+            ops.push((ItemOp::MoveUp, labels.move_up.clone()));
+        }
+        if self.reorder && index + 1 < items.len() {
+            ops.push((ItemOp::MoveDown, labels.move_down.clone()));
+        }
+        ops.push((ItemOp::Delete, labels.delete.clone()));
+        let mut display_options: Vec<String> = ops.iter().map(|(_, label)| label.clone()).collect();
+        display_options.extend(labels.label(BuiltIn::Back));
+        let response = self
+            .window
+            .clone()
+            .prompt(items[index].to_string())
+            .lines(display_options.len() as i32)
+            .select(display_options);
+        let op = match response {
+            Ok(Response::Row(row)) if row < ops.len() => ops[row].0,
+            Ok(_) => return Ok(None),
+            Err(_) => return Err("error getting user input from rofi".to_string())
+        };
+        let name = items[index].to_string();
+        match op {
+            ItemOp::Edit => {
+                let edit = self.edit.as_mut().expect("edit is offered only when set");
+                let item = &items[index];
+                match guarded(|| edit(item))? {
+                    Some(edited) => items[index] = edited,
+                    None => return Ok(None)
+                }
+                Ok(Some((index, format!("edit {}", name))))
+            }
+            ItemOp::Custom(i) => {
+                let (label, callback) = &mut self.actions[i];
+                let item = &mut items[index];
+                guarded(|| callback(item))?;
+                Ok(Some((index, format!("{} {}", label, name))))
+            }
+            ItemOp::MoveUp => {
+                items.swap(index, index - 1);
+                Ok(Some((index - 1, format!("move {}", name))))
+            }
+            ItemOp::MoveDown => {
+                items.swap(index, index + 1);
+                Ok(Some((index + 1, format!("move {}", name))))
+            }
+            ItemOp::Delete => {
+                let question = format!("Delete '{}'?", name);
+                if self.confirm_delete && !Confirm::new().display(question) {
+                    return Ok(None);
+                }
+                items.remove(index);
+                let row = index.min(items.len().saturating_sub(1));
+                Ok(Some((row, format!("delete {}", name))))
+            }
+        }
+    }
+}
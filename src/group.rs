@@ -0,0 +1,280 @@
+//! A `Grouping` splits the items of an `AppPage` or `ItemList` into groups shown under a header
+//! row, like todos grouped by status. Headers are rows rofi can't select, so they can't be mistaken
+//! for items. Items are shown group by group, keeping their order within a group, so a
+//! `UsageTracker` still ranks items inside each group.
+//!
+//! Collapsible groups get selectable headers instead: selecting one folds or unfolds its items.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::components::ItemList;
+//! use rustofi::group::Grouping;
+//!
+//! let todos = vec!["TODO buy milk".to_string(), "DONE call mom".to_string()];
+//! ItemList::new(todos, Box::new(|_| Ok(())))
+//!     .grouping(
+//!         Grouping::by(Box::new(|t: &String| t.split(' ').next().unwrap_or("").to_string()))
+//!             .order(vec!["TODO".to_string(), "DONE".to_string()])
+//!             .collapsible(true)
+//!             .collapse("DONE")
+//!     )
+//!     .display("Todos".to_string());
+//! ```
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+
+use crate::window::{escape_markup, Row};
+
+/// returns the name of the group an item belongs to
+pub type GroupKey<T> = Box<dyn Fn(&T) -> String>;
+
+/// how the items of a list are grouped under headers
+pub struct Grouping<T> {
+    /// name of the group of each item
+    pub key: GroupKey<T>,
+    /// groups shown first, in this order. Other groups follow in order of first appearance
+    pub order: Vec<String>,
+    /// whether selecting a header folds or unfolds its group
+    pub collapsible: bool,
+    /// groups currently folded
+    pub collapsed: HashSet<String>
+}
+
+/// a row of a grouped list
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GroupRow {
+    /// header of the named group
+    Header(String),
+    /// item at this index of the list
+    Item(usize)
+}
+
+impl<T> Grouping<T> {
+    /// group items by the name `key` returns for them
+    pub fn by(key: GroupKey<T>) -> Self {
+        Grouping {
+            key,
+            order: Vec::new(),
+            collapsible: false,
+            collapsed: HashSet::new()
+        }
+    }
+
+    /// show these groups first, in this order
+    pub fn order(mut self, order: Vec<String>) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// let the user fold and unfold groups by selecting their header
+    pub fn collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// start with the group `name` folded
+    pub fn collapse(mut self, name: &str) -> Self {
+        self.collapsed.insert(name.to_string());
+        self
+    }
+
+    /// fold the group `name` if it's unfolded and the other way around
+    pub fn toggle(&mut self, name: &str) {
+        if !self.collapsed.remove(name) {
+            self.collapsed.insert(name.to_string());
+        }
+    }
+
+    /// the rows showing `items`: every group's header followed by its items unless it's folded
+    fn layout(&self, items: &[T]) -> Vec<GroupRow> {
+        let keys: Vec<String> = items.iter().map(|item| (self.key)(item)).collect();
+        let mut groups: Vec<&String> = self.order.iter().filter(|g| keys.contains(g)).collect();
+        for key in &keys {
+            if !groups.contains(&key) {
+                groups.push(key);
+            }
+        }
+        let mut rows = Vec::new();
+        for group in groups {
+            rows.push(GroupRow::Header(group.clone()));
+            if !self.collapsed.contains(group) {
+                let members = keys.iter().enumerate().filter(|(_, key)| *key == group);
+                rows.extend(members.map(|(i, _)| GroupRow::Item(i)));
+            }
+        }
+        rows
+    }
+
+    /// the header row of the group `name`, holding `count` items
+    fn header(&self, name: &str, count: usize) -> Row {
+        let title = format!("<b>{}</b>", escape_markup(name));
+        if !self.collapsible {
+            Row::new(&title).nonselectable(true)
+        } else if self.collapsed.contains(name) {
+            Row::new(&format!("▸ {}  <small>({})</small>", title, count))
+        } else {
+            Row::new(&format!("▾ {}", title))
+        }
+    }
+}
+
+/// what the rows of a list stand for. Without a grouping row `i` is item `i`, so nothing is stored
+/// and finding the item of a row is free
+pub(crate) enum Layout {
+    /// one row per item, in order
+    Flat(usize),
+    /// headers and the items of unfolded groups
+    Grouped(Vec<GroupRow>)
+}
+
+impl Layout {
+    /// number of rows
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Layout::Flat(len) => *len,
+            Layout::Grouped(rows) => rows.len()
+        }
+    }
+
+    /// what row `row` stands for
+    pub(crate) fn get(&self, row: usize) -> Option<GroupRow> {
+        match self {
+            Layout::Flat(len) => Some(GroupRow::Item(row)).filter(|_| row < *len),
+            Layout::Grouped(rows) => rows.get(row).cloned()
+        }
+    }
+}
+
+/// a row of a window, written straight to rofi without building a string for it first
+pub(crate) enum Line<'i, T> {
+    /// an item, also matched on the text given
+    Item(&'i T, Option<&'i str>),
+    /// a row with options, like a group header
+    Row(Row),
+    /// a built-in entry
+    Label(&'i str)
+}
+
+impl<'i, T: Display> Display for Line<'i, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Line::Item(item, None) => write!(f, "{}", item),
+            Line::Item(item, Some(meta)) => write!(f, "{}\0meta\x1f{}", item, meta),
+            Line::Row(row) => write!(f, "{}", row),
+            Line::Label(label) => f.write_str(label)
+        }
+    }
+}
+
+/// the layout of `items`, grouped if there's a `grouping`
+pub(crate) fn layout<T>(grouping: &Option<Grouping<T>>, items: &[T]) -> Layout {
+    match grouping {
+        Some(grouping) => Layout::Grouped(grouping.layout(items)),
+        None => Layout::Flat(items.len())
+    }
+}
+
+/// the lines showing `items` as laid out by `layout`, all matched on `meta` too if there is one
+pub(crate) fn lines<'i, T: Display>(
+    grouping: &'i Option<Grouping<T>>, layout: &'i Layout, items: &'i [T], meta: Option<&'i str>
+) -> Box<dyn Iterator<Item = Line<'i, T>> + 'i> {
+    let (grouping, rows) = match (grouping, layout) {
+        (Some(grouping), Layout::Grouped(rows)) => (grouping, rows),
+        _ => return Box::new(items.iter().map(move |item| Line::Item(item, meta)))
+    };
+    Box::new(rows.iter().map(move |row| match row {
+        GroupRow::Header(name) => {
+            let count = items.iter().filter(|item| (grouping.key)(item) == *name).count();
+            let header = grouping.header(name, count);
+            Line::Row(match meta {
+                Some(meta) => header.meta(meta),
+                None => header
+            })
+        }
+        GroupRow::Item(i) => Line::Item(&items[*i], meta)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// todos grouped by their first word
+    fn by_status() -> Grouping<&'static str> {
+        Grouping::by(Box::new(|t: &&str| t.split(' ').next().unwrap_or("").to_string()))
+    }
+
+    const TODOS: [&str; 4] = ["DONE call mom", "TODO buy milk", "DONE pay rent", "TODO fix bike"];
+
+    fn shown(grouping: Option<Grouping<&'static str>>) -> Vec<String> {
+        let layout = layout(&grouping, &TODOS);
+        lines(&grouping, &layout, &TODOS, None).map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn groups_follow_first_appearance_keeping_item_order() {
+        use GroupRow::*;
+        let rows = by_status().layout(&TODOS);
+        let done = Header("DONE".to_string());
+        let todo = Header("TODO".to_string());
+        assert_eq!(rows, vec![done, Item(0), Item(2), todo, Item(1), Item(3)]);
+    }
+
+    #[test]
+    fn ordered_groups_come_first() {
+        let grouping = by_status().order(vec!["TODO".to_string(), "LATER".to_string()]);
+        let rows = grouping.layout(&TODOS);
+        assert_eq!(rows[0], GroupRow::Header("TODO".to_string()));
+        assert_eq!(rows[3], GroupRow::Header("DONE".to_string()));
+        // groups without items get no header
+        assert_eq!(rows.len(), 6);
+    }
+
+    #[test]
+    fn collapsed_groups_hide_their_items_until_toggled() {
+        let mut grouping = by_status().collapsible(true).collapse("DONE");
+        let rows = grouping.layout(&TODOS);
+        assert_eq!(rows[0], GroupRow::Header("DONE".to_string()));
+        assert_eq!(rows[1], GroupRow::Header("TODO".to_string()));
+        grouping.toggle("DONE");
+        assert_eq!(grouping.layout(&TODOS).len(), 6);
+        grouping.toggle("TODO");
+        assert_eq!(grouping.layout(&TODOS).len(), 4);
+    }
+
+    #[test]
+    fn headers() {
+        let lines = shown(Some(by_status()));
+        assert_eq!(lines[0], "<b>DONE</b>\0nonselectable\x1ftrue");
+        assert_eq!(lines[1], "DONE call mom");
+        // collapsed headers count their hidden items
+        let lines = shown(Some(by_status().collapsible(true).collapse("TODO")));
+        assert_eq!(lines[0], "▾ <b>DONE</b>");
+        assert_eq!(lines[3], "▸ <b>TODO</b>  <small>(2)</small>");
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn rows_map_back_to_items() {
+        let grouping = Some(by_status());
+        let grouped = layout(&grouping, &TODOS);
+        assert_eq!(grouped.get(0), Some(GroupRow::Header("DONE".to_string())));
+        assert_eq!(grouped.get(2), Some(GroupRow::Item(2)));
+        assert_eq!(grouped.get(4), Some(GroupRow::Item(1)));
+        assert_eq!(grouped.get(6), None);
+        let flat = layout::<&str>(&None, &TODOS);
+        assert_eq!(flat.get(3), Some(GroupRow::Item(3)));
+        assert_eq!(flat.get(4), None);
+    }
+
+    #[test]
+    fn lines_match_the_meta() {
+        let grouping = Some(by_status());
+        let layout = layout(&grouping, &TODOS);
+        let lines: Vec<String> =
+            lines(&grouping, &layout, &TODOS, Some("q")).map(|l| l.to_string()).collect();
+        assert_eq!(lines[0], "<b>DONE</b>\0meta\x1fq\x1fnonselectable\x1ftrue");
+        assert_eq!(lines[1], "DONE call mom\0meta\x1fq");
+        assert_eq!(shown(None), TODOS.to_vec());
+    }
+}
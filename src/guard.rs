@@ -0,0 +1,149 @@
+//! By default a panic inside an `item_callback` (or any other component callback) unwinds through
+//! the component and tears down the whole application. Setting a `PanicPolicy` makes every
+//! component catch those panics and turn them into a `RustofiResult::Error` instead, optionally
+//! showing the panic message in a rofi error window first.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::components::ItemList;
+//! use rustofi::guard::{set_panic_policy, PanicPolicy};
+//! use rustofi::{CallbackResult, RustofiResult};
+//!
+//! fn main() {
+//!     // show panics to the user instead of crashing
+//!     set_panic_policy(PanicPolicy::Display);
+//!     let entries = vec!["Entry 1".to_string(), "Entry 2".to_string()];
+//!     let callback = |_: &mut String| -> CallbackResult { panic!("oops") };
+//!     let mut list = ItemList::new(entries, Box::new(callback));
+//!     match list.display("Select an entry".to_string()) {
+//!         // the panic message, after it was displayed in a rofi `-e` window
+//!         RustofiResult::Error(m) => println!("{}", m),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::components::MessageBox;
+use crate::window::escape_markup;
+
+/// how panics raised inside component callbacks are handled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PanicPolicy {
+    /// let the panic unwind through the component, the default
+    Propagate = 0,
+    /// catch the panic and return it as a `RustofiResult::Error`
+    Catch = 1,
+    /// catch the panic, show it in a rofi error window then return it as a `RustofiResult::Error`.
+    /// `RustofiResult::display_error` doesn't show it a second time
+    Display = 2
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(PanicPolicy::Propagate as u8);
+
+thread_local! {
+    /// message of the last panic shown by `guarded`, until `display_error` comes across it
+    static SHOWN: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// set the policy used by every component for panics raised in callbacks
+pub fn set_panic_policy(policy: PanicPolicy) {
+    POLICY.store(policy as u8, Ordering::SeqCst);
+}
+
+/// get the policy currently used for panics raised in callbacks
+pub fn panic_policy() -> PanicPolicy {
+    match POLICY.load(Ordering::SeqCst) {
+        1 => PanicPolicy::Catch,
+        2 => PanicPolicy::Display,
+        _ => PanicPolicy::Propagate
+    }
+}
+
+/// run a component callback under the current `PanicPolicy`
+pub(crate) fn guarded<R, F: FnOnce() -> Result<R, String>>(callback: F) -> Result<R, String> {
+    let policy = panic_policy();
+    if policy == PanicPolicy::Propagate {
+        return callback();
+    }
+    match panic::catch_unwind(AssertUnwindSafe(callback)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = format!("callback panicked: {}", panic_message(&payload));
+            if policy == PanicPolicy::Display {
+                MessageBox::error().display(escape_markup(&message));
+                SHOWN.with(|shown| *shown.borrow_mut() = Some(message.clone()));
+            }
+            Err(message)
+        }
+    }
+}
+
+/// whether `message` is the panic `guarded` just showed. It's only reported once, the same
+/// message coming up again is a new error
+pub(crate) fn already_shown(message: &str) -> bool {
+    SHOWN.with(|shown| {
+        let mut shown = shown.borrow_mut();
+        let same = shown.as_deref() == Some(message);
+        if same {
+            *shown = None;
+        }
+        same
+    })
+}
+
+/// extract the message given to `panic!` from a caught panic payload
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload<P: Any + Send>(payload: P) -> Box<dyn Any + Send> {
+        Box::new(payload)
+    }
+
+    #[test]
+    fn panic_messages() {
+        assert_eq!(panic_message(&payload("oops")), "oops");
+        assert_eq!(panic_message(&payload(format!("oops {}", 1))), "oops 1");
+        assert_eq!(panic_message(&payload(1)), "unknown panic");
+    }
+
+    // the policy is global, so every policy is tried in the same test
+    #[test]
+    fn policies() {
+        assert_eq!(panic_policy(), PanicPolicy::Propagate);
+        let propagated = panic::catch_unwind(|| guarded::<(), _>(|| panic!("oops")));
+        assert!(propagated.is_err());
+        assert_eq!(guarded(|| Ok(1)), Ok(1));
+
+        set_panic_policy(PanicPolicy::Catch);
+        assert_eq!(panic_policy(), PanicPolicy::Catch);
+        let caught = guarded::<(), _>(|| panic!("oops {}", 2));
+        assert_eq!(caught, Err("callback panicked: oops 2".to_string()));
+        // caught panics aren't shown, so they're left to `display_error`
+        assert!(!already_shown("callback panicked: oops 2"));
+        assert_eq!(guarded::<(), _>(|| Err("failed".to_string())), Err("failed".to_string()));
+        set_panic_policy(PanicPolicy::Propagate);
+    }
+
+    #[test]
+    fn shown_panics_are_reported_once() {
+        SHOWN.with(|shown| *shown.borrow_mut() = Some("callback panicked: oops".to_string()));
+        assert!(!already_shown("other"));
+        assert!(already_shown("callback panicked: oops"));
+        assert!(!already_shown("callback panicked: oops"));
+    }
+}
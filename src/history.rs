@@ -0,0 +1,152 @@
+//! A `History` remembers the most recent entries typed into an `EntryBox` so they can be offered
+//! as suggestions the next time it opens. Entries are stored one per line in a plain text file,
+//! most recent first, under `$XDG_DATA_HOME/rustofi/history` unless a path is given explicitly.
+//!
+//! Suggestions are normal rows, so Return picks the highlighted one even when the typed text only
+//! matches part of it. rofi's `kb-accept-custom` binding (Control+Return by default) enters the
+//! text exactly as typed.
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::xdg::data_dir;
+
+/// persisted list of previous entries, most recent first
+#[derive(Debug, Clone)]
+pub struct History {
+    /// file the entries are stored in
+    pub path: PathBuf,
+    /// maximum number of entries kept
+    pub limit: usize
+}
+
+impl History {
+    /// create a history named `name` stored in the rustofi data directory
+    pub fn new(name: &str) -> Self {
+        History::at(data_dir().join("history").join(name))
+    }
+
+    /// create a history stored in the file at `path`
+    pub fn at(path: PathBuf) -> Self {
+        History { path, limit: 50 }
+    }
+
+    /// set the maximum number of entries kept
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// read the stored entries, most recent first. A missing file is an empty history
+    pub fn entries(&self) -> Vec<String> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => contents
+                .lines()
+                .filter(|l| !l.is_empty())
+                .take(self.limit)
+                .map(|l| l.to_string())
+                .collect(),
+            Err(_) => Vec::new()
+        }
+    }
+
+    /// record `entry` as the most recent one, moving it to the front if it was already stored
+    pub fn push(&self, entry: &str) -> io::Result<()> {
+        let entry = entry.replace("\n", "");
+        let mut entries = self.entries();
+        entries.retain(|e| *e != entry);
+        entries.insert(0, entry);
+        entries.truncate(self.limit);
+        self.write(&entries)
+    }
+
+    /// remove `entry` from the history
+    pub fn forget(&self, entry: &str) -> io::Result<()> {
+        let mut entries = self.entries();
+        entries.retain(|e| e != entry);
+        self.write(&entries)
+    }
+
+    /// remove every stored entry
+    pub fn clear(&self) -> io::Result<()> {
+        self.write(&[])
+    }
+
+    fn write(&self, entries: &[String]) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut contents = entries.join("\n");
+        contents.push('\n');
+        fs::write(&self.path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a history stored in a fresh file under the temp dir
+    fn history(name: &str) -> History {
+        let path = std::env::temp_dir()
+            .join(format!("rustofi-history-{}", std::process::id()))
+            .join(name);
+        let _ = fs::remove_file(&path);
+        History::at(path)
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        assert!(history("missing").entries().is_empty());
+    }
+
+    #[test]
+    fn most_recent_first() {
+        let history = history("order");
+        for entry in &["a", "b", "c"] {
+            history.push(entry).unwrap();
+        }
+        assert_eq!(history.entries(), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn repeated_entries_move_to_the_front() {
+        let history = history("repeat");
+        for entry in &["a", "b", "c", "a"] {
+            history.push(entry).unwrap();
+        }
+        assert_eq!(history.entries(), vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn limit_drops_the_oldest() {
+        let history = history("limit").limit(2);
+        for entry in &["a", "b", "c"] {
+            history.push(entry).unwrap();
+        }
+        assert_eq!(history.entries(), vec!["c", "b"]);
+        // a lower limit also applies to entries stored before it was set
+        assert_eq!(history.limit(1).entries(), vec!["c"]);
+    }
+
+    #[test]
+    fn entries_stay_on_one_line() {
+        let history = history("newline");
+        history.push("two\nlines").unwrap();
+        assert_eq!(history.entries(), vec!["twolines"]);
+    }
+
+    #[test]
+    fn forget_and_clear() {
+        let history = history("forget");
+        for entry in &["a", "b", "c"] {
+            history.push(entry).unwrap();
+        }
+        history.forget("b").unwrap();
+        assert_eq!(history.entries(), vec!["c", "a"]);
+        history.forget("missing").unwrap();
+        assert_eq!(history.entries(), vec!["c", "a"]);
+        history.clear().unwrap();
+        assert!(history.entries().is_empty());
+    }
+}
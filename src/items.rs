@@ -0,0 +1,128 @@
+//! What `AppPage` and `ItemList` do with a selected or edited item, kept in one place so the two
+//! behave the same
+use std::fmt::Display;
+
+use crate::actionable::ItemMenu;
+use crate::editor::{edit_item, InlineEdit};
+use crate::group::GroupRow;
+use crate::guard::guarded;
+use crate::labels::Labels;
+use crate::store::{save_change, save_items, SharedStore};
+use crate::undo::UndoStack;
+use crate::usage::{record_selection, UsageTracker};
+use crate::RustofiCallback;
+
+/// the parts of an `AppPage` or `ItemList` acting on its items
+pub(crate) struct ItemHandlers<'c, T> {
+    pub(crate) callback: &'c mut Box<dyn RustofiCallback<T>>,
+    pub(crate) menu: &'c mut Option<ItemMenu<T>>,
+    pub(crate) edit: &'c mut Option<InlineEdit<T>>,
+    pub(crate) store: &'c Option<SharedStore<T>>,
+    pub(crate) usage: &'c Option<UsageTracker>,
+    pub(crate) undo: &'c Option<UndoStack>
+}
+
+impl<'c, T: Display + Clone> ItemHandlers<'c, T> {
+    /// open the menu of the item at `index`, or run the item callback on it without one. If
+    /// either ran the items are saved and the selection recorded, a change to the text of a stored
+    /// item is also recorded in the undo history. `order` is the position of each item in the
+    /// store, `None` for items that aren't stored like live search results. Returns the text of
+    /// the selected item, or `None` when the list is shown again after the item menu
+    pub(crate) fn select(
+        &mut self, labels: &Labels, items: &mut [T], index: usize, order: Option<&[usize]>
+    ) -> Result<Option<String>, String> {
+        let (item, callback) = (&mut items[index], &mut *self.callback);
+        let label = item.to_string();
+        let ran = match self.menu {
+            Some(menu) => menu(item, labels)?,
+            None => guarded(|| callback(item)).map(|_| true)?
+        };
+        let selection = item.to_string();
+        if ran {
+            match order {
+                Some(order) if selection != label => {
+                    let change = format!("change {}", label);
+                    save_change(self.store, items, order, self.undo, &change)?
+                }
+                Some(order) => save_items(self.store, items, order)?,
+                None => {}
+            }
+            record_selection(self.usage, &label)?;
+        }
+        Ok(Some(selection).filter(|_| self.menu.is_none()))
+    }
+
+    /// edit the item `row` stands for with the inline edit, saving the change and recording it in
+    /// the undo history if the items are stored. Only items can be edited, other rows are left
+    /// alone
+    pub(crate) fn edit(
+        &mut self, items: &mut [T], row: Option<GroupRow>, order: Option<&[usize]>
+    ) -> Result<(), String> {
+        let index = match row {
+            Some(GroupRow::Item(index)) => index,
+            _ => return Ok(())
+        };
+        match (edit_item(self.edit, items, index)?, order) {
+            (Some(change), Some(order)) => {
+                save_change(self.store, items, order, self.undo, &change)
+            }
+            _ => Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    /// select `items[index]` with `callback` the way a stored `ItemList` does
+    fn select(
+        callback: Box<dyn RustofiCallback<String>>, store: &Option<SharedStore<String>>,
+        undo: &Option<UndoStack>, items: &mut [String], index: usize
+    ) -> Option<String> {
+        let (mut callback, mut menu, mut edit) = (callback, None, None);
+        let mut handlers = ItemHandlers {
+            callback: &mut callback,
+            menu: &mut menu,
+            edit: &mut edit,
+            store,
+            usage: &None,
+            undo
+        };
+        let order: Vec<usize> = (0..items.len()).collect();
+        handlers.select(&Labels::default(), items, index, Some(&order)).unwrap()
+    }
+
+    #[test]
+    fn callback_changes_can_be_undone() {
+        let store = Some(SharedStore::new(MemoryStore::new(vec!["x".to_string()])));
+        let stack = UndoStack::new();
+        let undo = Some(stack.clone());
+        let stored = || store.as_ref().unwrap().store.borrow_mut().load().unwrap();
+        let mut items = stored();
+        let toggle = || -> Box<dyn RustofiCallback<String>> {
+            Box::new(|item: &mut String| {
+                item.push('!');
+                Ok(())
+            })
+        };
+        assert_eq!(select(toggle(), &store, &undo, &mut items, 0), Some("x!".to_string()));
+        assert!(stack.can_undo());
+        select(toggle(), &store, &undo, &mut items, 0);
+        assert_eq!(stored(), vec!["x!!"]);
+        assert_eq!(stack.undo(), Ok(Some("change x!".to_string())));
+        assert_eq!(stack.undo(), Ok(Some("change x".to_string())));
+        assert_eq!(stored(), vec!["x"]);
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn unchanged_items_record_nothing() {
+        let store = Some(SharedStore::new(MemoryStore::new(vec!["x".to_string()])));
+        let stack = UndoStack::new();
+        let mut items = vec!["x".to_string()];
+        select(Box::new(|_: &mut String| Ok(())), &store, &Some(stack.clone()), &mut items, 0);
+        assert!(!stack.can_undo());
+    }
+}
@@ -0,0 +1,151 @@
+//! Every window rustofi builds adds a few entries of its own: `[exit]` on an `AppPage`, `[cancel]`
+//! on lists, `[submit]` on a `Form` and so on. `Labels` holds the text of all of them so they can
+//! be translated or renamed, either for every component through `set_global` or for a single
+//! component through its `labels` method. Setting a row's label to `None` hides the row.
+//!
+//! Built-in rows are always recognised by their position, never by their text, so a user item
+//! that happens to be called `[exit]` can't be mistaken for the real one.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::labels::Labels;
+//!
+//! Labels::set_global(Labels {
+//!     exit: Some("[quitter]".to_string()),
+//!     cancel: Some("[annuler]".to_string()),
+//!     yes: "Oui".to_string(),
+//!     no: "Non".to_string(),
+//!     ..Labels::default()
+//! });
+//! ```
+use std::sync::RwLock;
+
+static GLOBAL: RwLock<Option<Labels>> = RwLock::new(None);
+
+/// text of the entries rustofi adds to its windows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Labels {
+    /// row leaving an `AppPage`, returns `RustofiResult::Exit`
+    pub exit: Option<String>,
+    /// row leaving a list or form, returns `RustofiResult::Cancel`
+    pub cancel: Option<String>,
+    /// row going back up a level, from live search results or in a `TreeMenu`
+    pub back: Option<String>,
+    /// row separating the items of a list from its built-in rows, hidden by default
+    pub separator: Option<String>,
+    /// row submitting a `Form`
+    pub submit: String,
+    /// row closing a `Checklist` with the checked items
+    pub done: String,
+    /// row undoing the latest operation of an `UndoStack`
+    pub undo: Option<String>,
+    /// row redoing the latest undone operation of an `UndoStack`
+    pub redo: Option<String>,
+    /// row adding an item in a `ListEditor`
+    pub add: Option<String>,
+    /// row showing the next page of a `PagedList`
+    pub next_page: Option<String>,
+    /// row showing the previous page of a `PagedList`
+    pub previous_page: Option<String>,
+    /// item menu entry of a `ListEditor` editing the item
+    pub edit: String,
+    /// item menu entry of a `ListEditor` deleting the item
+    pub delete: String,
+    /// item menu entry of a `ListEditor` moving the item one row up
+    pub move_up: String,
+    /// item menu entry of a `ListEditor` moving the item one row down
+    pub move_down: String,
+    /// affirmative answer of a `Confirm`
+    pub yes: String,
+    /// negative answer of a `Confirm`
+    pub no: String,
+    /// `MessageButton::Ok`
+    pub ok: String,
+    /// `MessageButton::Retry`
+    pub retry: String
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Labels {
+            exit: Some("[exit]".to_string()),
+            cancel: Some("[cancel]".to_string()),
+            back: Some("[back]".to_string()),
+            separator: None,
+            submit: "[submit]".to_string(),
+            done: "[done]".to_string(),
+            undo: Some("[undo]".to_string()),
+            redo: Some("[redo]".to_string()),
+            add: Some("[add]".to_string()),
+            next_page: Some("[next page]".to_string()),
+            previous_page: Some("[previous page]".to_string()),
+            edit: "[edit]".to_string(),
+            delete: "[delete]".to_string(),
+            move_up: "[move up]".to_string(),
+            move_down: "[move down]".to_string(),
+            yes: "Yes".to_string(),
+            no: "No".to_string(),
+            ok: "OK".to_string(),
+            retry: "Retry".to_string()
+        }
+    }
+}
+
+impl Labels {
+    /// the labels used by components without labels of their own
+    pub fn global() -> Labels {
+        match GLOBAL.read() {
+            Ok(labels) => labels.clone().unwrap_or_default(),
+            Err(_) => Labels::default()
+        }
+    }
+
+    /// set the labels used by components without labels of their own
+    pub fn set_global(labels: Labels) {
+        if let Ok(mut global) = GLOBAL.write() {
+            *global = Some(labels);
+        }
+    }
+}
+
+/// a row rustofi adds to a window by itself
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BuiltIn {
+    Exit,
+    Cancel,
+    Back,
+    Separator,
+    Submit,
+    Done,
+    Add,
+    NextPage,
+    PreviousPage,
+    Undo,
+    Redo
+}
+
+impl Labels {
+    /// label of a built-in row, `None` if the row is hidden
+    pub(crate) fn label(&self, row: BuiltIn) -> Option<String> {
+        match row {
+            BuiltIn::Exit => self.exit.clone(),
+            BuiltIn::Cancel => self.cancel.clone(),
+            BuiltIn::Back => self.back.clone(),
+            BuiltIn::Separator => self.separator.clone(),
+            BuiltIn::Submit => Some(self.submit.clone()),
+            BuiltIn::Done => Some(self.done.clone()),
+            BuiltIn::Add => self.add.clone(),
+            BuiltIn::NextPage => self.next_page.clone(),
+            BuiltIn::PreviousPage => self.previous_page.clone(),
+            BuiltIn::Undo => self.undo.clone(),
+            BuiltIn::Redo => self.redo.clone()
+        }
+    }
+
+    /// the visible rows out of `rows` together with their labels, in order
+    pub(crate) fn rows(&self, rows: &[BuiltIn]) -> Vec<(BuiltIn, String)> {
+        rows.iter()
+            .filter_map(|row| self.label(*row).map(|label| (*row, label)))
+            .collect()
+    }
+}
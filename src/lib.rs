@@ -17,7 +17,7 @@
 //! ```no_run
 //! // examples/simple.rs
 //! use rustofi::components::ItemList;
-//! use rustofi::RustofiResult;
+//! use rustofi::{CallbackResult, RustofiResult};
 //!
 //! fn simple_app() -> RustofiResult {
 //!     // create a list of strings to pass as rofi options. Note that this can be any type you want,
@@ -31,20 +31,19 @@
 //!     ItemList::new(rustofi_entries, Box::new(simple_callback)).display("Select an entry".to_string())
 //! }
 //!
-//! pub fn simple_callback(s: &String) -> RustofiResult {
+//! pub fn simple_callback(s: &mut String) -> CallbackResult {
 //!     // when an item is clicked, print the name!
 //!     println!("Clicked on item: {}", s);
-//!     RustofiResult::Success
+//!     Ok(())
 //! }
 //!
 //! fn main() {
 //!     loop {
 //!         match simple_app() {
 //!             //!  loop unless the user requests we exit
-//!             RustofiResult::Error => break,
+//!             RustofiResult::Error(_) => break,
 //!             RustofiResult::Exit => break,
 //!             RustofiResult::Cancel => break,
-//!             RustofiResult::Blank => break,
 //!             _ => {}
 //!         }
 //!     }
@@ -53,29 +52,77 @@
 
 /// extra rofi window types usable to create an application, essentially navigation result pages
 pub mod components;
+/// add, edit, delete and reorder the items of a list kept in a `Store`, or edit them in place
+pub mod editor;
+/// actions listed by the items themselves, offered in a menu when one is selected
+pub mod actionable;
 /// the error(s) returned by this crate
 pub mod errors;
+/// opt-in handling of panics raised inside component callbacks
+pub mod guard;
+/// items shown in groups under header rows
+pub mod group;
+/// persisted entry history offered as suggestions by `EntryBox`
+pub mod history;
+/// configurable text of the entries rustofi adds to its windows
+pub mod labels;
+/// traits turning Rust types into rofi menus and forms, derivable with the `derive` feature
+pub mod menu;
+/// very large lists shown one page at a time, fetched lazily
+pub mod paged;
+/// one query sent to several search providers at once, their results grouped under headers
+pub mod search;
+/// storage backends for the lists components edit
+pub mod store;
+/// nested menus walked in a single window, with breadcrumbs in the prompt
+pub mod tree;
+/// undo and redo of the changes made through components
+pub mod undo;
+/// frecency ranking of page items from their on-disk selection history
+pub mod usage;
 /// raw representation of a rofi command, use this to create new components, or your own from-scratch
 /// apps
 pub mod window;
+/// selecting and editing the items of `AppPage` and `ItemList`
+mod items;
+mod xdg;
 
-use crate::window::{Dimensions, Location, Window};
+pub use crate::menu::{RofiForm, RofiMenu};
+#[cfg(feature = "derive")]
+pub use rustofi_derive::{RofiForm, RofiMenu};
+
+use crate::actionable::{item_menu, Actionable, ItemMenu};
+use crate::components::{confirm_question, Confirm, MessageBox};
+use crate::editor::InlineEdit;
+use crate::group::{GroupRow, Grouping, Line};
+use crate::guard::{already_shown, guarded};
+use crate::items::ItemHandlers;
+use crate::labels::{BuiltIn, Labels};
+use crate::store::{load_items, SharedStore, Store};
+use crate::undo::{undo_rows, UndoStack};
+use crate::usage::{sort_items, UsageTracker};
+use crate::window::{escape_markup, Dimensions, Location, Response, Row, Window};
 use std::clone::Clone;
 use std::fmt::Display;
 
 pub type CallbackResult = std::result::Result<(), String>;
 /// enum declaring all possible return values from a rofi window constructed
 /// using this library. Callbacks should also generally return this type, specifying
-/// `Success`, `Error`, `Exit` or `Cancel` in most cases
-pub enum RustofiResult {
+/// `Success`, `Error`, `Exit` or `Cancel` in most cases. `A` is the type of the actions offered by
+/// the window, plain strings unless typed actions were given
+pub enum RustofiResult<A = String> {
     /// A standard item
     Selection(String),
     /// An action item
-    Action(String),
+    Action(A),
     /// The operation completed successfully
     Success,
-    /// The blank entry was selected. Note this entry isn't actually blank but a single space
-    Blank,
+    /// The component's `PrimaryAction` was selected, by its row or its keybinding
+    Primary,
+    /// The operation with this label was undone through `[undo]` or its keybinding
+    Undone(String),
+    /// The operation with this label was redone through `[redo]` or its keybinding
+    Redone(String),
     /// Something went wrong creating the rofi window or in the callback
     Error(String),
     /// `ItemList` or `ActionList` was cancelled, used to return to a main menu
@@ -84,6 +131,21 @@ pub enum RustofiResult {
     Exit
 }
 
+impl<A> RustofiResult<A> {
+    /// if this is an `Error`, show its message to the user in an error `MessageBox`. Panics
+    /// already shown under `PanicPolicy::Display` aren't shown again. The result is returned
+    /// unchanged so it can still be matched on afterwards
+    pub fn display_error(self) -> Self {
+        match &self {
+            RustofiResult::Error(m) if !already_shown(m) => {
+                MessageBox::error().display(escape_markup(m));
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
 /// Wrapper around a callback that returns a RustofiResult
 pub trait RustofiCallback<T>: FnMut(&mut T) -> CallbackResult {
     fn clone_boxed(&self) -> Box<dyn RustofiCallback<T>>;
@@ -102,55 +164,375 @@ impl<T: 'static> Clone for Box<dyn RustofiCallback<T>> {
     }
 }
 
+/// callback run when an `Action` is selected, with the action's value
+pub type ActionCallback<A> = Box<dyn FnMut(&A) -> CallbackResult>;
+
+/// an action offered by an `AppPage` or `ActionList`. The value can be any type, selecting the
+/// action returns it as `RustofiResult::Action`. Each action can carry its own callback, a
+/// description and a keybinding that selects it directly
+pub struct Action<A> {
+    /// value returned when the action is selected
+    pub value: A,
+    /// text shown for the action
+    pub label: String,
+    /// name or path of an icon shown next to the action
+    pub icon: Option<String>,
+    /// short explanation shown next to the label
+    pub description: Option<String>,
+    /// rofi key combination selecting the action, like `Alt+a`
+    pub keybinding: Option<String>,
+    /// whether to ask for confirmation with a `Confirm` before the action is selected
+    pub confirm: bool,
+    /// disabled actions are shown greyed out and can't be selected
+    pub enabled: bool,
+    /// run when the action is selected, before it is returned
+    pub callback: Option<ActionCallback<A>>
+}
+
+impl<A: Display> Action<A> {
+    /// create an action shown using the value's `to_string()`
+    pub fn new(value: A) -> Self {
+        let label = value.to_string();
+        Action::labeled(value, label)
+    }
+}
+
+impl<A: RofiMenu> Action<A> {
+    /// create an action from a menu entry, shown with its label and icon. The label is plain
+    /// text, so it's escaped to show as is among markup rows
+    pub fn menu(value: A) -> Self {
+        let label = escape_markup(&value.label());
+        let icon = value.icon();
+        Action {
+            icon,
+            ..Action::labeled(value, label)
+        }
+    }
+
+    /// create an action for every entry of the menu, in display order
+    pub fn menu_entries() -> Vec<Self> {
+        A::entries().into_iter().map(Action::menu).collect()
+    }
+}
+
+impl<A> Action<A> {
+    /// create an action showing `label` for a value
+    pub fn labeled(value: A, label: String) -> Self {
+        Action {
+            value,
+            label,
+            icon: None,
+            description: None,
+            keybinding: None,
+            confirm: false,
+            enabled: true,
+            callback: None
+        }
+    }
+
+    /// set the icon shown next to the action
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// set the description shown next to the action
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// set the key combination selecting the action, using rofi's syntax (`Control+d`, `Alt+a`)
+    pub fn keybinding(mut self, keybinding: &str) -> Self {
+        self.keybinding = Some(keybinding.to_string());
+        self
+    }
+
+    /// ask for confirmation before the action is selected. Declining returns
+    /// `RustofiResult::Cancel` so neither the action's callback nor the caller's handler runs
+    pub fn confirm(mut self, confirm: bool) -> Self {
+        self.confirm = confirm;
+        self
+    }
+
+    /// show the action greyed out and ignore its keybinding while it's not `enabled`
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// set the callback run when the action is selected
+    pub fn callback(mut self, callback: ActionCallback<A>) -> Self {
+        self.callback = Some(callback);
+        self
+    }
+
+    /// the row shown for this action, including its description and keybinding
+    pub(crate) fn row(&self) -> String {
+        let mut text = self.label.clone();
+        if let Some(description) = &self.description {
+            text.push_str(&format!("  <small><i>{}</i></small>", escape_markup(description)));
+        }
+        if let Some(keybinding) = &self.keybinding {
+            text.push_str(&format!("  <small>{}</small>", escape_markup(keybinding)));
+        }
+        if !self.enabled {
+            text = format!("<span alpha=\"50%\">{}</span>", text);
+        }
+        let row = Row::new(&text).nonselectable(!self.enabled);
+        match &self.icon {
+            Some(icon) => row.icon(icon).to_string(),
+            None => row.to_string()
+        }
+    }
+
+    /// ask for confirmation if needed then run the callback. Returns whether the action went ahead
+    pub(crate) fn trigger(&mut self) -> Result<bool, String> {
+        if !self.enabled {
+            return Ok(false);
+        }
+        if self.confirm && !Confirm::new().display(confirm_question(&self.label)) {
+            return Ok(false);
+        }
+        if let Some(callback) = &mut self.callback {
+            let value = &self.value;
+            guarded(|| callback(value))?;
+        }
+        Ok(true)
+    }
+}
+
+impl<A: Display> From<A> for Action<A> {
+    fn from(value: A) -> Self {
+        Action::new(value)
+    }
+}
+
+/// callback run when a `PrimaryAction` is selected
+pub type PrimaryCallback = Box<dyn FnMut() -> CallbackResult>;
+
+/// the main thing to do on a page besides picking an item, like "add a new todo". It's shown as a
+/// labeled row right after the items and can also be bound to a key. Selecting it runs its
+/// callback and returns `RustofiResult::Primary`
+pub struct PrimaryAction {
+    /// text shown for the row
+    pub label: String,
+    /// name or path of an icon shown next to the label
+    pub icon: Option<String>,
+    /// rofi key combination selecting the action, like `Alt+n`
+    pub keybinding: Option<String>,
+    /// run when the action is selected, before `RustofiResult::Primary` is returned
+    pub callback: Option<PrimaryCallback>
+}
+
+impl PrimaryAction {
+    /// create a primary action shown as `label`
+    pub fn new(label: &str) -> Self {
+        PrimaryAction {
+            label: label.to_string(),
+            icon: None,
+            keybinding: None,
+            callback: None
+        }
+    }
+
+    /// set the icon shown next to the label
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// set the key combination selecting the action, using rofi's syntax (`Control+n`, `Alt+a`)
+    pub fn keybinding(mut self, keybinding: &str) -> Self {
+        self.keybinding = Some(keybinding.to_string());
+        self
+    }
+
+    /// set the callback run when the action is selected
+    pub fn callback(mut self, callback: PrimaryCallback) -> Self {
+        self.callback = Some(callback);
+        self
+    }
+
+    /// the row shown for this action, including its keybinding
+    pub(crate) fn row(&self) -> String {
+        let mut text = self.label.clone();
+        if let Some(keybinding) = &self.keybinding {
+            text.push_str(&format!("  <small>{}</small>", escape_markup(keybinding)));
+        }
+        match &self.icon {
+            Some(icon) => Row::new(&text).icon(icon).to_string(),
+            None => text
+        }
+    }
+
+    /// run the callback and build the matching result
+    pub(crate) fn trigger<A>(&mut self) -> RustofiResult<A> {
+        let result = match &mut self.callback {
+            Some(callback) => guarded(callback),
+            None => Ok(())
+        };
+        match result {
+            Ok(_) => RustofiResult::Primary,
+            Err(m) => RustofiResult::Error(m)
+        }
+    }
+}
+
+/// what a custom key set up by `keybinding_args` is bound to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Bound {
+    Primary,
+    Edit,
+    Search,
+    Action(usize),
+    Undo,
+    Redo
+}
+
+/// every keybinding of a component, in the order they get custom keys: the primary action, the
+/// component's own `keys` like editing the highlighted item, the actions then undo and redo
+fn bindings<A>(
+    primary: Option<&PrimaryAction>, keys: &[(Bound, &str)], actions: &[Action<A>],
+    undo: Option<&UndoStack>
+) -> Vec<(Bound, String)> {
+    let mut bindings = Vec::new();
+    if let Some(keybinding) = primary.and_then(|p| p.keybinding.clone()) {
+        bindings.push((Bound::Primary, keybinding));
+    }
+    bindings.extend(keys.iter().map(|(bound, keybinding)| (*bound, keybinding.to_string())));
+    for (i, action) in actions.iter().enumerate().filter(|(_, a)| a.enabled) {
+        if let Some(keybinding) = &action.keybinding {
+            bindings.push((Bound::Action(i), keybinding.clone()));
+        }
+    }
+    if let Some((undo_key, redo_key)) = undo.and_then(|u| u.keybindings.clone()) {
+        bindings.push((Bound::Undo, undo_key));
+        bindings.push((Bound::Redo, redo_key));
+    }
+    bindings.truncate(19);
+    bindings
+}
+
+/// rofi args binding the keybindings of a component to custom keys
+pub(crate) fn keybinding_args<A>(
+    primary: Option<&PrimaryAction>, keys: &[(Bound, &str)], actions: &[Action<A>],
+    undo: Option<&UndoStack>
+) -> Vec<String> {
+    let mut args = Vec::new();
+    let bindings = bindings(primary, keys, actions, undo);
+    for (key, (_, keybinding)) in bindings.into_iter().enumerate() {
+        args.push(format!("-kb-custom-{}", key + 1));
+        args.push(keybinding);
+    }
+    if primary.is_some_and(|p| p.icon.is_some()) || actions.iter().any(|a| a.icon.is_some()) {
+        args.push("-show-icons".to_string());
+    }
+    args
+}
+
+/// what custom key `key` was bound to by `keybinding_args`
+pub(crate) fn bound_to_key<A>(
+    primary: Option<&PrimaryAction>, keys: &[(Bound, &str)], actions: &[Action<A>],
+    undo: Option<&UndoStack>, key: usize
+) -> Option<Bound> {
+    let bindings = bindings(primary, keys, actions, undo);
+    key.checked_sub(1)
+        .and_then(|key| bindings.get(key))
+        .map(|(bound, _)| *bound)
+}
+
 /// Trait implemented by `AppPage`
 pub trait RustofiComponent<'a> {
+    /// type of the actions offered by the component
+    type Action;
     /// returns a rofi window with special initial options for the implementation
     fn create_window() -> Window<'a>;
-    /// set the callback associated with the blank entry item
-    fn blank(self, bcb: Box<dyn FnMut() -> CallbackResult>) -> Self;
-    /// set the optional actions to display
-    fn actions(self, actions: Vec<String>) -> Self;
+    /// set the primary action, shown right after the items
+    fn primary(self, primary: PrimaryAction) -> Self;
+    /// set the optional actions to display, either plain values or configured `Action`s
+    fn actions<I: Into<Action<Self::Action>>>(self, actions: Vec<I>) -> Self;
+    /// set every entry of the action type's menu as the optional actions
+    fn menu_actions(self) -> Self
+    where
+        Self: Sized,
+        Self::Action: RofiMenu
+    {
+        self.actions(Action::<Self::Action>::menu_entries())
+    }
     /// customize the implementation's rofi window
     fn window(self, window: Window<'a>) -> Self;
     /// run the rofi command
-    fn display(&mut self, prompt: String) -> RustofiResult;
+    fn display(&mut self, prompt: String) -> RustofiResult<Self::Action>;
 }
 
+/// returns the items matching a query typed in an `AppPage`
+pub type LiveSearch<T> = Box<dyn FnMut(&String) -> Result<Vec<T>, String>>;
+
 /// `AppPage` displays a multi column rofi window with items and actions listed.
 /// If you set search_callback, non-matching user input will run a custom function,
 /// use this to implement features like adding to lists or searching web results.
 /// `items` should be associated with a data model, while `actions`
 /// should be either operations you can perform on those items, or actions you can take
-/// within the app (switch pages for example).
+/// within the app (switch pages for example). Actions can be of any type `A`, the selected one is
+/// returned as is in `RustofiResult::Action`.
 /// The `search_callback` allows you to refresh the data models displayed or
-/// perform an operation on custom entry
-pub struct AppPage<'a, T> {
+/// perform an operation on custom entry, while `live_search` turns custom entry into a query whose
+/// results replace the items
+pub struct AppPage<'a, T, A = String> {
     /// standard list items, will be displayed in the rofi window using to_string()
     pub items: Vec<T>,
     /// callback called whenever an item in the `items` vector is selected
     pub item_callback: Box<dyn RustofiCallback<T>>,
     /// additional action entries, meant to be operations on standard items
-    pub actions: Vec<String>,
-    /// callback called whenever a blank entry is selected
-    pub blank_callback: Box<dyn FnMut() -> CallbackResult>,
+    pub actions: Vec<Action<A>>,
+    /// main action of the page, shown right after the items
+    pub primary: Option<PrimaryAction>,
     /// callback to be run when no other entry matches
     pub search_callback: Box<dyn FnMut(&String) -> CallbackResult>,
+    /// returns the items matching custom entry, replaces `search_callback` when set
+    pub live_search: Option<LiveSearch<T>>,
+    /// rofi key combination running the live search again for the typed text
+    pub search_key: String,
+    /// ranks the items by how often and how recently they were selected
+    pub usage: Option<UsageTracker>,
+    /// shows the items in groups under header rows
+    pub grouping: Option<Grouping<T>>,
+    /// offers to undo and redo the operations recorded in it
+    pub undo: Option<UndoStack>,
+    /// keeps the items, loaded when displaying and saved after the item callback ran
+    pub store: Option<SharedStore<T>>,
+    /// opened on the selected item instead of running `item_callback`
+    pub item_menu: Option<ItemMenu<T>>,
+    /// edits the highlighted item when its keybinding is pressed
+    pub edit: Option<InlineEdit<T>>,
+    /// labels of the built-in entries, the global ones when `None`
+    pub labels: Option<Labels>,
     /// rofi window instance
     pub window: Window<'a>
 }
 
-impl<'a, T: Display + Clone> AppPage<'a, T> {
+impl<'a, T: Display + Clone, A: Clone> AppPage<'a, T, A> {
     /// create the initial bare minumum AppPage, without showing the window yet
-    pub fn new(
-        items: Vec<T>, item_callback: Box<dyn RustofiCallback<T>>, actions: Vec<String>
+    pub fn new<I: Into<Action<A>>>(
+        items: Vec<T>, item_callback: Box<dyn RustofiCallback<T>>, actions: Vec<I>
     ) -> Self {
         AppPage {
             items,
             item_callback,
             actions: Vec::new(),
-            blank_callback: Box::new(|| Ok(())),
+            primary: None,
             search_callback: Box::new(|_| Ok(())),
-            window: AppPage::<T>::create_window()
+            live_search: None,
+            search_key: "Alt+Return".to_string(),
+            usage: None,
+            grouping: None,
+            undo: None,
+            store: None,
+            item_menu: None,
+            edit: None,
+            labels: None,
+            window: AppPage::<T, A>::create_window()
         }
         .actions(actions)
     }
@@ -160,10 +542,112 @@ impl<'a, T: Display + Clone> AppPage<'a, T> {
         self.search_callback = scb;
         self
     }
+
+    /// search for non-matching input: the window is shown again with the returned items in place
+    /// of the page's own, the query kept in the filter and a `[back]` entry returning to the
+    /// page's items. Selecting a result runs the item callback on it, the search key searches
+    /// again for the typed text
+    pub fn live_search(mut self, search: LiveSearch<T>) -> Self {
+        self.live_search = Some(search);
+        self
+    }
+
+    /// set the key combination running the live search for the typed text, `Alt+Return` by
+    /// default. Return searches too while the text matches no row
+    pub fn search_key(mut self, key: &str) -> Self {
+        self.search_key = key.to_string();
+        self
+    }
+
+    /// show the items most used with `usage` first and record every item selected
+    pub fn usage(mut self, usage: UsageTracker) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// show the items in groups under header rows
+    pub fn grouping(mut self, grouping: Grouping<T>) -> Self {
+        self.grouping = Some(grouping);
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// offer `[undo]` and `[redo]` for the operations recorded in `undo`
+    pub fn undo(mut self, undo: UndoStack) -> Self {
+        self.undo = Some(undo);
+        self
+    }
+
+    /// show the list kept in `store` instead of the items given, loaded every time the window is
+    /// displayed and saved back after the item callback ran
+    pub fn store<S: Store<T> + 'static>(mut self, store: S) -> Self
+    where
+        T: 'static
+    {
+        self.store = Some(SharedStore::new(store));
+        self
+    }
+
+    /// open a menu of the selected item's actions instead of running the item callback, showing
+    /// the list again once it's left
+    pub fn item_actions(mut self) -> Self
+    where
+        T: Actionable + 'static
+    {
+        self.item_menu = Some(item_menu());
+        self
+    }
+
+    /// edit the highlighted item when `edit`'s keybinding is pressed, showing the list again
+    /// afterwards
+    pub fn edit(mut self, edit: InlineEdit<T>) -> Self {
+        self.edit = Some(edit);
+        self
+    }
+
+    fn trigger_primary(&mut self) -> RustofiResult<A> {
+        match &mut self.primary {
+            Some(primary) => primary.trigger(),
+            None => RustofiResult::Cancel
+        }
+    }
+
+    fn trigger_undo(&self, redo: bool) -> RustofiResult<A> {
+        match &self.undo {
+            Some(undo) => undo.trigger(redo),
+            None => RustofiResult::Cancel
+        }
+    }
+
+    /// the results of the live search for `query`, `None` without a live search
+    fn live_results(&mut self, query: &String) -> Option<Result<Vec<T>, String>> {
+        let live_search = self.live_search.as_mut()?;
+        Some(guarded(|| live_search(query)))
+    }
+
+    /// the items along with what acts on them
+    fn handlers(&mut self) -> (&mut Vec<T>, ItemHandlers<'_, T>) {
+        let handlers = ItemHandlers {
+            callback: &mut self.item_callback,
+            menu: &mut self.item_menu,
+            edit: &mut self.edit,
+            store: &self.store,
+            usage: &self.usage,
+            undo: &self.undo
+        };
+        (&mut self.items, handlers)
+    }
 }
 
-impl<'a, T: Display + Clone> RustofiComponent<'a> for AppPage<'a, T> {
-    /// create a rofi window with 4 columns
+impl<'a, T: Display + Clone, A: Clone> RustofiComponent<'a> for AppPage<'a, T, A> {
+    type Action = A;
+
+    /// create a rofi window with 4 columns, shown once the first 100 options are read
     fn create_window() -> Window<'a> {
         Window::new("Search")
             .format('s')
@@ -175,20 +659,20 @@ impl<'a, T: Display + Clone> RustofiComponent<'a> for AppPage<'a, T> {
                 columns: 4
             })
             .add_args(vec!["-markup-rows".to_string()])
+            .async_pre_read(100)
     }
 
-    /// set the callback to be run when the blank entry is selected
-    fn blank(mut self, bcb: Box<dyn FnMut() -> CallbackResult>) -> Self {
-        self.blank_callback = bcb;
+    /// set the primary action, shown between the items and the other actions
+    fn primary(mut self, primary: PrimaryAction) -> Self {
+        self.primary = Some(primary);
         self
     }
 
     /// set the actions in the AppPage. This should only be called once as it overwrites
-    /// the previous settings
-    fn actions(mut self, mut actions: Vec<String>) -> Self {
-        actions.insert(0, " ".to_string());
-        actions.push("[exit]".to_string());
-        self.actions = actions;
+    /// the previous settings. The primary action and `[exit]` are shown around them, `[exit]`
+    /// can be hidden through `labels`
+    fn actions<I: Into<Action<A>>>(mut self, actions: Vec<I>) -> Self {
+        self.actions = actions.into_iter().map(|a| a.into()).collect();
         self
     }
 
@@ -198,55 +682,198 @@ impl<'a, T: Display + Clone> RustofiComponent<'a> for AppPage<'a, T> {
         self
     }
 
-    /// display the search window and match the entry against the actions, standard items
-    /// and finally if nothing matches, run the search callback
-    fn display(&mut self, prompt: String) -> RustofiResult {
-        let mut display_options: Vec<String> = self.items.iter().map(|s| s.to_string()).collect();
-        display_options.append(&mut self.actions.clone());
-        let response = self
-            .window
-            .clone()
-            .prompt(prompt)
-            .lines(display_options.clone().len() as i32)
-            .show(display_options.clone());
-
-        match response {
-            Ok(input) => {
-                if input == "[exit]" {
-                    RustofiResult::Exit
-                } else if input == " " {
-                    match (self.blank_callback)() {
-                        Ok(_) => return RustofiResult::Blank,
-                        Err(m) => return RustofiResult::Error(m)
+    /// display the search window and match the entry against the standard items and actions,
+    /// and finally if nothing matches, run the search callback. With a live search set, the
+    /// window is shown again with the results of the query until one is picked or `[back]`
+    /// returns to the items. With item actions, selecting an item opens its menu and the page is
+    /// shown again afterwards
+    fn display(&mut self, prompt: String) -> RustofiResult<A> {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        // held until the window is done with, so other instances can't change the list meanwhile
+        let _lock = match load_items(&self.store, &mut self.items) {
+            Ok(lock) => lock,
+            Err(m) => return RustofiResult::Error(m)
+        };
+        let order = sort_items(&self.usage, &mut self.items);
+        // query and results of the live search being shown, if any
+        let mut search: Option<(String, Vec<T>)> = None;
+        let mut selected_row = 0;
+        loop {
+            let (items, query) = match &search {
+                Some((query, results)) => (results, Some(query)),
+                None => (&self.items, None)
+            };
+            // matched on the query too so the filter doesn't hide the way back
+            let back = query.and_then(|query| {
+                labels.label(BuiltIn::Back).map(|back| Row::new(&back).meta(query).to_string())
+            });
+            let mut tail = undo_rows(&self.undo);
+            tail.push(BuiltIn::Exit);
+            let tail = labels.rows(&tail);
+            let primary = self.primary.as_ref();
+            let mut keys: Vec<(Bound, &str)> =
+                self.edit.iter().map(|e| (Bound::Edit, e.keybinding.as_str())).collect();
+            if self.live_search.is_some() {
+                keys.push((Bound::Search, &self.search_key));
+            }
+            // rows are laid out as items, back, primary action, actions then undo, redo and exit,
+            // the built-in entries are told apart by position so an item can share their label
+            let layout = group::layout(&self.grouping, items);
+            let back_row = layout.len();
+            let primary_row = back_row + back.is_some() as usize;
+            let first_action = primary_row + self.primary.is_some() as usize;
+            let tail_row = first_action + self.actions.len();
+            let mut labelled: Vec<String> = back.into_iter().collect();
+            labelled.extend(primary.map(|p| p.row()));
+            labelled.extend(self.actions.iter().map(|a| a.row()));
+            // items are streamed to rofi as they're written instead of collected first. Results
+            // don't have to contain the query, match them on it anyway so the filter doesn't
+            // hide them
+            let lines = group::lines(&self.grouping, &layout, items, query.map(|q| q.as_str()))
+                .chain(labelled.iter().map(|label| Line::Label(label)))
+                .chain(tail.iter().map(|(_, label)| Line::Label(label)));
+            let mut window = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .lines((tail_row + tail.len()) as i32)
+                .add_args(vec!["-selected-row".to_string(), selected_row.to_string()])
+                .add_args(keybinding_args(primary, &keys, &self.actions, self.undo.as_ref()));
+            if let Some(query) = query {
+                window = window.add_args(vec!["-filter".to_string(), query.clone()]);
+            }
+            let response = window.select(lines);
+
+            let action = match response {
+                Ok(Response::Row(row)) if row < back_row => {
+                    let index = match layout.get(row) {
+                        Some(GroupRow::Item(index)) => index,
+                        // only collapsible groups have selectable headers
+                        Some(GroupRow::Header(name)) => {
+                            if let Some(grouping) = &mut self.grouping {
+                                grouping.toggle(&name);
+                            }
+                            selected_row = row;
+                            continue;
+                        }
+                        None => return RustofiResult::Cancel
+                    };
+                    let (items, mut handlers) = self.handlers();
+                    // live search results aren't part of the stored list
+                    let (items, order) = match &mut search {
+                        Some((_, results)) => (results, None),
+                        None => (items, Some(order.as_slice()))
+                    };
+                    match handlers.select(&labels, items, index, order) {
+                        Err(m) => return RustofiResult::Error(m),
+                        Ok(None) => {
+                            selected_row = row;
+                            continue;
+                        }
+                        Ok(Some(selection)) => return RustofiResult::Selection(selection)
                     }
-                } else if input == "" {
-                    RustofiResult::Cancel
-                } else {
-                    // check if the entry matches one of the list items
-                    for mut item in self.items.clone() {
-                        if input == item.to_string() {
-                            match (self.item_callback)(&mut item) {
-                                Ok(_) => return RustofiResult::Selection(input),
-                                Err(m) => return RustofiResult::Error(m)
+                }
+                Ok(Response::Row(row)) if row < primary_row => {
+                    search = None;
+                    selected_row = 0;
+                    continue;
+                }
+                Ok(Response::Row(row)) if row < first_action => return self.trigger_primary(),
+                Ok(Response::Row(row)) if row < tail_row => row - first_action,
+                Ok(Response::Row(row)) => {
+                    return match tail.get(row - tail_row).map(|(b, _)| *b) {
+                        Some(BuiltIn::Undo) => self.trigger_undo(false),
+                        Some(BuiltIn::Redo) => self.trigger_undo(true),
+                        _ => RustofiResult::Exit
+                    };
+                }
+                Ok(Response::Key { key, row, filter }) => {
+                    let undo = self.undo.as_ref();
+                    match bound_to_key(primary, &keys, &self.actions, undo, key) {
+                        Some(Bound::Action(action)) => action,
+                        Some(Bound::Primary) => return self.trigger_primary(),
+                        Some(Bound::Edit) => {
+                            let (items, mut handlers) = self.handlers();
+                            let (items, order) = match &mut search {
+                                Some((_, results)) => (results, None),
+                                None => (items, Some(order.as_slice()))
+                            };
+                            let item_row = row.and_then(|row| layout.get(row));
+                            if let Err(m) = handlers.edit(items, item_row, order) {
+                                return RustofiResult::Error(m);
                             }
+                            selected_row = row.unwrap_or(0);
+                            continue;
                         }
+                        // the rows hold the results of the last query, a longer one typed since
+                        // would select them instead of searching so it's searched with the key
+                        Some(Bound::Search) => match self.live_results(&filter) {
+                            Some(Ok(results)) => {
+                                search = Some((filter, results));
+                                selected_row = 0;
+                                continue;
+                            }
+                            Some(Err(m)) => return RustofiResult::Error(m),
+                            None => return RustofiResult::Cancel
+                        },
+                        Some(Bound::Undo) => return self.trigger_undo(false),
+                        Some(Bound::Redo) => return self.trigger_undo(true),
+                        None => return RustofiResult::Cancel
                     }
-
-                    // check if the entry matches one of the action items
-                    for item in self.actions.clone() {
-                        if input == item.to_string() {
-                            return RustofiResult::Action(input);
+                }
+                Ok(Response::Entry(input)) => {
+                    // if the entry isn't an action or an existing entry item, search for it
+                    // or run the search callback
+                    match self.live_results(&input) {
+                        Some(Ok(results)) => {
+                            search = Some((input, results));
+                            selected_row = 0;
+                            continue;
                         }
+                        Some(Err(m)) => return RustofiResult::Error(m),
+                        None => {}
                     }
-                    // if the entry isn't an action or an existing entry item,
-                    // run the search callback
-                    match (self.search_callback)(&input) {
-                        Ok(_) => return RustofiResult::Selection(input),
-                        Err(m) => return RustofiResult::Error(m)
+                    return match guarded(|| (self.search_callback)(&input)) {
+                        Ok(_) => RustofiResult::Selection(input),
+                        Err(m) => RustofiResult::Error(m)
                     };
                 }
-            }
-            Err(_) => RustofiResult::Error("error getting user input from rofi".to_string())
+                Ok(Response::Cancel) => return RustofiResult::Cancel,
+                Err(_) => {
+                    return RustofiResult::Error("error getting user input from rofi".to_string())
+                }
+            };
+            let action = &mut self.actions[action];
+            return match action.trigger() {
+                Ok(true) => RustofiResult::Action(action.value.clone()),
+                Ok(false) => RustofiResult::Cancel,
+                Err(m) => RustofiResult::Error(m)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_keys_follow_the_binding_order() {
+        let primary = PrimaryAction::new("add").keybinding("Alt+a");
+        let keys = [(Bound::Edit, "Alt+e"), (Bound::Search, "Alt+Return")];
+        let actions = vec![Action::new("x"), Action::new("y").keybinding("Alt+y")];
+        let undo = UndoStack::new().keybindings("Control+z", "Control+y");
+        let args = keybinding_args(Some(&primary), &keys, &actions, Some(&undo));
+        assert_eq!(args[..4], ["-kb-custom-1", "Alt+a", "-kb-custom-2", "Alt+e"]);
+        assert_eq!(args[4..6], ["-kb-custom-3", "Alt+Return"]);
+        assert_eq!(args[10..], ["-kb-custom-6", "Control+y"]);
+        let bound = |key| bound_to_key(Some(&primary), &keys, &actions, Some(&undo), key);
+        let expected = [Bound::Primary, Bound::Edit, Bound::Search, Bound::Action(1), Bound::Undo];
+        for (key, expected) in expected.iter().enumerate() {
+            assert_eq!(bound(key + 1), Some(*expected));
         }
+        assert_eq!(bound(6), Some(Bound::Redo));
+        assert_eq!(bound(0), None);
+        assert_eq!(bound(7), None);
     }
 }
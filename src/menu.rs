@@ -0,0 +1,107 @@
+//! `RofiMenu` and `RofiForm` describe how a Rust type is shown by rofi. They can be implemented by
+//! hand but are meant to be derived with the `derive` feature (enabled by default):
+//!
+//! ```no_run
+//! use rustofi::components::ActionList;
+//! use rustofi::{RofiForm, RofiMenu};
+//!
+//! #[derive(Clone, RofiMenu)]
+//! enum Command {
+//!     #[rofi(label = "Add a task", icon = "list-add")]
+//!     Add,
+//!     #[rofi(label = "Edit the task")]
+//!     Edit,
+//!     Quit
+//! }
+//!
+//! #[derive(Clone, RofiForm)]
+//! struct Task {
+//!     #[rofi(label = "Task")]
+//!     name: String,
+//!     priority: u8,
+//!     done: bool
+//! }
+//!
+//! fn main() {
+//!     let mut task = Task { name: "water the plants".to_string(), priority: 1, done: false };
+//!     // selecting an entry gives back the variant itself, no string matching needed
+//!     match Command::select("What now?".to_string()) {
+//!         Ok(Some(Command::Edit)) => {
+//!             if let Ok(Some(edited)) = task.clone().form().display("Edit".to_string()) {
+//!                 task = edited;
+//!             }
+//!         }
+//!         Ok(Some(Command::Add)) | Ok(Some(Command::Quit)) | Ok(None) | Err(_) => {}
+//!     }
+//!     // menus work as actions too, the callback receives the variant
+//!     ActionList::menu(task.name, |name: &String, command: &Command| {
+//!         println!("{} on {}", command.label(), name);
+//!         Ok(())
+//!     })
+//!     .display("Pick an action".to_string());
+//! }
+//! ```
+use crate::components::{Field, Form};
+use crate::window::{escape_markup, Location, Row, Window};
+
+/// a type whose values are the entries of a rofi menu, typically a derived enum
+pub trait RofiMenu: Sized {
+    /// every entry of the menu in display order
+    fn entries() -> Vec<Self>;
+
+    /// text shown for this entry
+    fn label(&self) -> String;
+
+    /// name or path of the icon shown next to this entry
+    fn icon(&self) -> Option<String> {
+        None
+    }
+
+    /// labels of every entry in display order
+    fn labels() -> Vec<String> {
+        Self::entries().iter().map(|e| e.label()).collect()
+    }
+
+    /// show the menu in its own rofi window and return the selected entry, or `Ok(None)` if the
+    /// window was closed without a selection. Labels are shown as they are, not as markup
+    fn select(prompt: String) -> Result<Option<Self>, String> {
+        let mut entries = Self::entries();
+        let rows: Vec<String> = entries
+            .iter()
+            .map(|e| {
+                let row = Row::new(&escape_markup(&e.label()));
+                match e.icon() {
+                    Some(icon) => row.icon(&icon).to_string(),
+                    None => row.to_string()
+                }
+            })
+            .collect();
+        let mut window = Window::new("Menu")
+            .format('i')
+            .location(Location::MiddleCentre)
+            .prompt(prompt)
+            .lines(rows.len() as i32)
+            .add_args(vec!["-markup-rows".to_string()]);
+        if entries.iter().any(|e| e.icon().is_some()) {
+            window = window.add_args(vec!["-show-icons".to_string()]);
+        }
+        match window.show(rows) {
+            Ok(input) => match input.parse::<usize>() {
+                Ok(i) if i < entries.len() => Ok(Some(entries.swap_remove(i))),
+                _ => Ok(None)
+            },
+            Err(_) => Err("error getting user input from rofi".to_string())
+        }
+    }
+}
+
+/// a struct that can be edited field by field in a `Form`, typically derived
+pub trait RofiForm: Sized + Clone + 'static {
+    /// the editable fields of the struct in display order
+    fn fields() -> Vec<Field<Self>>;
+
+    /// create a `Form` editing this value
+    fn form<'a>(self) -> Form<'a, Self> {
+        Form::new(self, Self::fields())
+    }
+}
@@ -0,0 +1,282 @@
+//! A `PagedList` shows a list too large to hand to rofi at once, like hundreds of thousands of
+//! log lines or database rows, one page at a time. Pages are fetched lazily from a
+//! `PageProvider` when the user moves to them with `[next page]`, `[previous page]` or their
+//! keybindings, and the message bar tells which part of the list is shown.
+//!
+//! Rofi only filters the page being shown, so typing text matching no item of the page returns it
+//! as a `Selection`, as `ItemList` does.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::paged::PagedList;
+//!
+//! let total = 250_000;
+//! PagedList::new(
+//!     Box::new(move |page, page_size| {
+//!         let first = (page * page_size).min(total);
+//!         let last = (first + page_size).min(total);
+//!         Ok((first..last).map(|n| format!("row {}", n)).collect())
+//!     }),
+//!     Box::new(|row: &mut String| {
+//!         println!("picked {}", row);
+//!         Ok(())
+//!     })
+//! )
+//! .page_size(50)
+//! .total(total)
+//! .keybindings("Alt+Right", "Alt+Left")
+//! .display("Rows".to_string());
+//! ```
+use std::fmt::Display;
+
+use crate::guard::guarded;
+use crate::labels::{BuiltIn, Labels};
+use crate::window::{Location, Response, Window};
+use crate::{RustofiCallback, RustofiResult};
+
+/// returns the items of page `page`, counting from 0, holding up to `page_size` items
+pub type PageProvider<T> = Box<dyn FnMut(usize, usize) -> Result<Vec<T>, String>>;
+
+/// `PagedList` shows the items of a `PageProvider` one page at a time. Each item runs the same
+/// callback
+pub struct PagedList<'a, T> {
+    pub provider: PageProvider<T>,
+    pub item_callback: Box<dyn RustofiCallback<T>>,
+    /// number of items on a page
+    pub page_size: usize,
+    /// number of items in the whole list, when known. Without it a page is assumed to be followed
+    /// by another one when it's full
+    pub total: Option<usize>,
+    /// rofi key combinations showing the next and the previous page
+    pub keybindings: Option<(String, String)>,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a, T: Display> PagedList<'a, T> {
+    /// create a list fetching its pages from `provider`, 100 items at a time
+    pub fn new(provider: PageProvider<T>, item_callback: Box<dyn RustofiCallback<T>>) -> Self {
+        PagedList {
+            provider,
+            item_callback,
+            page_size: 100,
+            total: None,
+            keybindings: None,
+            labels: None,
+            window: PagedList::<T>::create_window()
+        }
+    }
+
+    /// create a list paging through `items`, already in memory
+    pub fn from_items(items: Vec<T>, item_callback: Box<dyn RustofiCallback<T>>) -> Self
+    where
+        T: Clone + 'static
+    {
+        let total = items.len();
+        let provider = move |page: usize, page_size: usize| {
+            let first = (page * page_size).min(items.len());
+            let last = (first + page_size).min(items.len());
+            Ok(items[first..last].to_vec())
+        };
+        PagedList::new(Box::new(provider), item_callback).total(total)
+    }
+
+    /// create a simple rofi instance in the middle of the screen rendering markup rows
+    fn create_window() -> Window<'a> {
+        Window::new("PagedList")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string()])
+    }
+
+    /// set the number of items on a page
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// set the number of items in the whole list, shown in the message bar
+    pub fn total(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// set the key combinations showing the next and the previous page
+    pub fn keybindings(mut self, next: &str, previous: &str) -> Self {
+        self.keybindings = Some((next.to_string(), previous.to_string()));
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// set a completely custom window. Its message is shown above the position in the list
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// show the list from its first page, moving between pages until an item is selected, which
+    /// runs the callback and returns `Selection`. Failing to fetch a page returns `Error`
+    pub fn display(&mut self, prompt: String) -> RustofiResult {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let mut page = 0;
+        // navigation row to keep highlighted on the new page, so it can be selected repeatedly
+        let mut highlighted = None;
+        loop {
+            let (provider, page_size) = (&mut self.provider, self.page_size);
+            let mut items = match guarded(|| provider(page, page_size)) {
+                Ok(items) => items,
+                Err(m) => return RustofiResult::Error(m)
+            };
+            let navigation = self.navigation(page, items.len());
+            let has_next = navigation.contains(&BuiltIn::NextPage);
+            let extra = labels.rows(&navigation);
+            let selected_row = highlighted
+                .and_then(|row| extra.iter().position(|(b, _)| *b == row))
+                .map_or(0, |position| items.len() + position);
+            let mut display_options: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+            display_options.extend(extra.iter().map(|(_, label)| label.clone()));
+            let position = self.position(page, items.len());
+            let message = match &self.window.message {
+                Some(message) => format!("{}\n{}", message, position),
+                None => position
+            };
+            let mut window = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .message(message)
+                .lines(display_options.len() as i32)
+                .add_args(vec!["-selected-row".to_string(), selected_row.to_string()]);
+            if let Some((next, previous)) = &self.keybindings {
+                window = window.add_args(vec![
+                    "-kb-custom-1".to_string(),
+                    next.clone(),
+                    "-kb-custom-2".to_string(),
+                    previous.clone(),
+                ]);
+            }
+            let moved = match window.select(display_options) {
+                Ok(Response::Row(row)) if row < items.len() => {
+                    let (item, callback) = (&mut items[row], &mut self.item_callback);
+                    return match guarded(|| callback(item)) {
+                        Ok(_) => RustofiResult::Selection(item.to_string()),
+                        Err(m) => RustofiResult::Error(m)
+                    };
+                }
+                Ok(Response::Row(row)) => match extra.get(row - items.len()).map(|(b, _)| *b) {
+                    Some(nav) if nav == BuiltIn::NextPage || nav == BuiltIn::PreviousPage => {
+                        highlighted = Some(nav);
+                        nav
+                    }
+                    _ => return RustofiResult::Cancel
+                },
+                Ok(Response::Key { key, .. }) if key == 1 || key == 2 => {
+                    highlighted = None;
+                    match key {
+                        1 => BuiltIn::NextPage,
+                        _ => BuiltIn::PreviousPage
+                    }
+                }
+                Ok(Response::Entry(input)) => return RustofiResult::Selection(input),
+                Ok(_) => return RustofiResult::Cancel,
+                Err(_) => {
+                    return RustofiResult::Error("error getting user input from rofi".to_string())
+                }
+            };
+            page = match moved {
+                BuiltIn::NextPage if has_next => page + 1,
+                BuiltIn::PreviousPage => page.saturating_sub(1),
+                _ => page
+            };
+        }
+    }
+
+    /// the built-in rows shown below the `count` items of `page`: the pages it's between, then
+    /// `[cancel]`
+    fn navigation(&self, page: usize, count: usize) -> Vec<BuiltIn> {
+        let has_next = match self.total {
+            Some(total) => (page + 1) * self.page_size < total,
+            None => count >= self.page_size
+        };
+        let mut navigation = Vec::new();
+        if page > 0 {
+            navigation.push(BuiltIn::PreviousPage);
+        }
+        if has_next {
+            navigation.push(BuiltIn::NextPage);
+        }
+        navigation.extend(&[BuiltIn::Separator, BuiltIn::Cancel]);
+        navigation
+    }
+
+    /// where the `count` items of `page` are in the list, like `page 2 of 40 · 101–200 of 4000`
+    fn position(&self, page: usize, count: usize) -> String {
+        let first = page * self.page_size;
+        let items = match count {
+            0 => "no items".to_string(),
+            _ => format!("{}–{}", first + 1, first + count)
+        };
+        match self.total {
+            Some(total) => {
+                let pages = total.div_ceil(self.page_size).max(1);
+                format!("page {} of {} · {} of {}", page + 1, pages, items, total)
+            }
+            None => format!("page {} · {}", page + 1, items)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BuiltIn::{Cancel, NextPage, PreviousPage, Separator};
+
+    fn list(total: Option<usize>) -> PagedList<'static, String> {
+        let list = PagedList::new(Box::new(|_, _| Ok(Vec::new())), Box::new(|_| Ok(())));
+        let list = list.page_size(10);
+        match total {
+            Some(total) => list.total(total),
+            None => list
+        }
+    }
+
+    #[test]
+    fn last_partial_page() {
+        let list = list(Some(25));
+        assert_eq!(list.position(2, 5), "page 3 of 3 · 21–25 of 25");
+        assert_eq!(list.navigation(2, 5), vec![PreviousPage, Separator, Cancel]);
+        assert_eq!(list.navigation(1, 10), vec![PreviousPage, NextPage, Separator, Cancel]);
+    }
+
+    #[test]
+    fn exact_multiple_of_the_page_size() {
+        let list = list(Some(20));
+        assert_eq!(list.position(1, 10), "page 2 of 2 · 11–20 of 20");
+        assert_eq!(list.navigation(0, 10), vec![NextPage, Separator, Cancel]);
+        // no empty page follows the last full one
+        assert_eq!(list.navigation(1, 10), vec![PreviousPage, Separator, Cancel]);
+    }
+
+    #[test]
+    fn empty_list() {
+        let list = list(Some(0));
+        assert_eq!(list.position(0, 0), "page 1 of 1 · no items of 0");
+        assert_eq!(list.navigation(0, 0), vec![Separator, Cancel]);
+    }
+
+    #[test]
+    fn unknown_total() {
+        let list = list(None);
+        assert_eq!(list.position(0, 10), "page 1 · 1–10");
+        // a full page may be followed by another one, a partial page is the last
+        assert_eq!(list.navigation(0, 10), vec![NextPage, Separator, Cancel]);
+        assert_eq!(list.navigation(3, 4), vec![PreviousPage, Separator, Cancel]);
+        assert_eq!(list.position(3, 0), "page 4 · no items");
+        assert_eq!(list.navigation(3, 0), vec![PreviousPage, Separator, Cancel]);
+    }
+}
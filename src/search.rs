@@ -0,0 +1,361 @@
+//! An `AggregatedSearchPage` sends one query to several sources at once, like a launcher looking
+//! through bookmarks, files and commands. Each source implements `SearchProvider`; the providers
+//! are queried in parallel and the results of each are shown under a header with its name. A
+//! provider that doesn't answer within its `time_limit` is left out of that query's results.
+//! Selecting a result hands it back to the provider it came from through `activate`.
+//!
+//! The first query is searched with Return. Once results are shown Return selects one, and the
+//! search key (`Alt+Return` by default) searches again for the typed text.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::search::{AggregatedSearchPage, SearchItem, SearchProvider};
+//! use rustofi::CallbackResult;
+//!
+//! struct Commands;
+//!
+//! impl SearchProvider for Commands {
+//!     fn name(&self) -> String {
+//!         "Commands".to_string()
+//!     }
+//!
+//!     fn search(&self, query: &str) -> Vec<SearchItem> {
+//!         ["firefox", "thunderbird", "htop"]
+//!             .iter()
+//!             .filter(|c| c.contains(query))
+//!             .map(|c| SearchItem::new(c))
+//!             .collect()
+//!     }
+//!
+//!     fn activate(&self, item: &SearchItem) -> CallbackResult {
+//!         std::process::Command::new(&item.id)
+//!             .spawn()
+//!             .map(|_| ())
+//!             .map_err(|e| e.to_string())
+//!     }
+//! }
+//!
+//! AggregatedSearchPage::new()
+//!     .provider(Commands)
+//!     .display("Search".to_string());
+//! ```
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::guard::guarded;
+use crate::labels::{BuiltIn, Labels};
+use crate::window::{escape_markup, Location, Response, Row, Window};
+use crate::{CallbackResult, RustofiResult};
+
+/// a single result returned by a `SearchProvider`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchItem {
+    /// text shown for the result
+    pub label: String,
+    /// name or path of an icon shown next to the result
+    pub icon: Option<String>,
+    /// identifies the result for its provider, the label unless set
+    pub id: String
+}
+
+impl SearchItem {
+    /// create a result showing `label`, also used as its id
+    pub fn new(label: &str) -> Self {
+        SearchItem {
+            label: label.to_string(),
+            icon: None,
+            id: label.to_string()
+        }
+    }
+
+    /// set the icon shown next to the result
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// set the id the provider recognises the result by
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+}
+
+/// a source of results for an `AggregatedSearchPage`. Providers are queried from their own
+/// thread, hence `Send + Sync`
+pub trait SearchProvider: Send + Sync {
+    /// name shown in the header above the provider's results
+    fn name(&self) -> String;
+    /// icon shown in the header above the provider's results
+    fn icon(&self) -> Option<String> {
+        None
+    }
+    /// how long the page waits for the provider's results before leaving them out
+    fn time_limit(&self) -> Duration {
+        Duration::from_millis(500)
+    }
+    /// the results matching `query`
+    fn search(&self, query: &str) -> Vec<SearchItem>;
+    /// run when one of the provider's results is selected
+    fn activate(&self, item: &SearchItem) -> CallbackResult;
+}
+
+/// `AggregatedSearchPage` asks every registered `SearchProvider` for results matching the typed
+/// query and shows them grouped by provider. Pressing the search key searches again with the typed
+/// text, selecting a result activates it with its provider
+pub struct AggregatedSearchPage<'a> {
+    pub providers: Vec<Arc<dyn SearchProvider>>,
+    /// rofi key combination searching for the typed text
+    pub search_key: String,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a> AggregatedSearchPage<'a> {
+    /// create a page without any providers
+    pub fn new() -> Self {
+        AggregatedSearchPage {
+            providers: Vec::new(),
+            search_key: "Alt+Return".to_string(),
+            labels: None,
+            window: AggregatedSearchPage::create_window()
+        }
+    }
+
+    /// create a rofi window in the middle of the screen able to show icons and markup
+    fn create_window() -> Window<'a> {
+        Window::new("Search")
+            .format('s')
+            .location(Location::MiddleCentre)
+            .add_args(vec!["-markup-rows".to_string(), "-show-icons".to_string()])
+    }
+
+    /// register a provider, its results are shown after those of the providers added before it
+    pub fn provider<P: SearchProvider + 'static>(mut self, provider: P) -> Self {
+        self.providers.push(Arc::new(provider));
+        self
+    }
+
+    /// set the key combination searching for the typed text, `Alt+Return` by default. Before the
+    /// first search Return works too
+    pub fn search_key(mut self, key: &str) -> Self {
+        self.search_key = key.to_string();
+        self
+    }
+
+    /// set a completely custom window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// query every provider in parallel, returning the results of each in registration order.
+    /// Providers that run past their time limit or panic get no results, their thread is left to
+    /// finish on its own
+    pub fn search(&self, query: &str) -> Vec<Vec<SearchItem>> {
+        let start = Instant::now();
+        let (sender, receiver) = mpsc::channel();
+        for (index, provider) in self.providers.iter().enumerate() {
+            let (provider, sender, query) = (provider.clone(), sender.clone(), query.to_string());
+            thread::spawn(move || {
+                let _ = sender.send((index, provider.search(&query)));
+            });
+        }
+        drop(sender);
+        let deadlines: Vec<Instant> = self.providers.iter().map(|p| start + p.time_limit()).collect();
+        let mut results = vec![None; self.providers.len()];
+        loop {
+            // wait for the provider with the latest deadline still missing
+            let pending = (0..results.len()).filter(|&i| results[i].is_none());
+            let deadline = match pending.map(|i| deadlines[i]).max() {
+                Some(deadline) => deadline,
+                None => break
+            };
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(timeout) {
+                Ok((index, items)) if Instant::now() <= deadlines[index] => {
+                    results[index] = Some(items)
+                }
+                Ok((index, _)) => results[index] = Some(Vec::new()),
+                Err(_) => break
+            }
+        }
+        results.into_iter().map(|r| r.unwrap_or_default()).collect()
+    }
+
+    /// show the results of the query typed in the window until one is selected and activated,
+    /// returning its label in a `RustofiResult::Selection`
+    pub fn display(&mut self, prompt: String) -> RustofiResult {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let mut query: Option<String> = None;
+        let mut results: Vec<Vec<SearchItem>> = Vec::new();
+        loop {
+            // every row along with the provider and result it stands for, `None` for the
+            // headers and built-in rows
+            let mut rows: Vec<(String, Option<(usize, usize)>)> = Vec::new();
+            // match every row on the query so the filter doesn't hide results
+            let matched = |row: Row| match &query {
+                Some(query) => row.meta(query),
+                None => row
+            };
+            for (p, items) in results.iter().enumerate().filter(|(_, items)| !items.is_empty()) {
+                let provider = &self.providers[p];
+                let name = format!("<b>{}</b>", escape_markup(&provider.name()));
+                let mut header = matched(Row::new(&name)).nonselectable(true);
+                if let Some(icon) = provider.icon() {
+                    header = header.icon(&icon);
+                }
+                rows.push((header.to_string(), None));
+                for (i, item) in items.iter().enumerate() {
+                    let mut row = matched(Row::new(&escape_markup(&item.label)));
+                    if let Some(icon) = &item.icon {
+                        row = row.icon(icon);
+                    }
+                    rows.push((row.to_string(), Some((p, i))));
+                }
+            }
+            // without rows Return hands back the typed text, a lone [cancel] would match most
+            // queries and get selected instead
+            if query.is_some() {
+                let cancel = labels.label(BuiltIn::Cancel);
+                rows.extend(cancel.map(|c| (matched(Row::new(&c)).to_string(), None)));
+            }
+            let mut window = self
+                .window
+                .clone()
+                .prompt(prompt.clone())
+                .lines(rows.len() as i32)
+                .add_args(vec!["-kb-custom-1".to_string(), self.search_key.clone()]);
+            if let Some(query) = &query {
+                window = window.add_args(vec!["-filter".to_string(), query.clone()]);
+                if results.iter().all(|items| items.is_empty()) {
+                    window = window.message(format!("no results for {}", escape_markup(query)));
+                }
+            }
+            let response = window.select(rows.iter().map(|(row, _)| row));
+            match response {
+                Ok(Response::Row(row)) => match rows.get(row).and_then(|(_, target)| *target) {
+                    Some((p, i)) => {
+                        let (provider, item) = (&self.providers[p], &results[p][i]);
+                        return match guarded(|| provider.activate(item)) {
+                            Ok(_) => RustofiResult::Selection(item.label.clone()),
+                            Err(m) => RustofiResult::Error(m)
+                        };
+                    }
+                    None => return RustofiResult::Cancel
+                },
+                // results match the last query, typing a longer one would select them instead of
+                // searching again so new searches go through the search key
+                Ok(Response::Key { key: 1, filter, .. }) | Ok(Response::Entry(filter)) => {
+                    results = self.search(&filter);
+                    query = Some(filter);
+                }
+                Ok(_) => return RustofiResult::Cancel,
+                Err(_) => {
+                    return RustofiResult::Error("error getting user input from rofi".to_string())
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Default for AggregatedSearchPage<'a> {
+    fn default() -> Self {
+        AggregatedSearchPage::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a provider answering `results` after `delay`
+    struct Provider {
+        results: Vec<&'static str>,
+        delay: Duration,
+        time_limit: Duration
+    }
+
+    impl Provider {
+        fn new(results: Vec<&'static str>, delay: u64) -> Self {
+            Provider {
+                results,
+                delay: Duration::from_millis(delay),
+                time_limit: Duration::from_millis(200)
+            }
+        }
+    }
+
+    impl SearchProvider for Provider {
+        fn name(&self) -> String {
+            "test".to_string()
+        }
+
+        fn time_limit(&self) -> Duration {
+            self.time_limit
+        }
+
+        fn search(&self, query: &str) -> Vec<SearchItem> {
+            thread::sleep(self.delay);
+            if query == "panic" {
+                panic!("search failed");
+            }
+            self.results.iter().map(|r| SearchItem::new(r)).collect()
+        }
+
+        fn activate(&self, _: &SearchItem) -> CallbackResult {
+            Ok(())
+        }
+    }
+
+    fn labels(results: Vec<Vec<SearchItem>>) -> Vec<Vec<String>> {
+        results.into_iter().map(|items| items.into_iter().map(|i| i.label).collect()).collect()
+    }
+
+    #[test]
+    fn results_keep_the_provider_order() {
+        let page = AggregatedSearchPage::new()
+            .provider(Provider::new(vec!["slow"], 80))
+            .provider(Provider::new(vec!["fast", "faster"], 0))
+            .provider(Provider::new(vec![], 40));
+        let results = labels(page.search("q"));
+        assert_eq!(results, vec![vec!["slow"], vec!["fast", "faster"], vec![]]);
+    }
+
+    #[test]
+    fn late_providers_are_left_out() {
+        let mut late = Provider::new(vec!["late"], 300);
+        late.time_limit = Duration::from_millis(50);
+        let page = AggregatedSearchPage::new()
+            .provider(late)
+            .provider(Provider::new(vec!["fast"], 0));
+        let start = Instant::now();
+        assert_eq!(labels(page.search("q")), vec![vec![], vec!["fast"]]);
+        // the page doesn't wait for the late provider past its own limit
+        assert!(start.elapsed() < Duration::from_millis(250));
+    }
+
+    #[test]
+    fn panicking_providers_get_no_results() {
+        let page = AggregatedSearchPage::new()
+            .provider(Provider::new(vec!["never"], 0))
+            .provider(Provider::new(vec!["never"], 10));
+        let start = Instant::now();
+        assert_eq!(labels(page.search("panic")), vec![Vec::<String>::new(), vec![]]);
+        // a panic ends the wait like a result does
+        assert!(start.elapsed() < Duration::from_millis(150));
+    }
+
+    #[test]
+    fn no_providers_no_results() {
+        assert!(AggregatedSearchPage::new().search("q").is_empty());
+    }
+}
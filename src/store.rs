@@ -0,0 +1,456 @@
+//! A `Store` is where a component keeps the list it edits, so changes made through the UI outlive
+//! the window. `MemoryStore` keeps the list in memory, handy for tests and for data that's saved
+//! some other way. `FileStore`, behind the default `file-store` feature, keeps it in a JSON or TOML
+//! file under `$XDG_DATA_HOME/rustofi/store`.
+//!
+//! Besides `ListEditor`, a store can back the items of an `AppPage` or `ItemList`: they're loaded
+//! from it whenever the window is displayed and saved back after the item callback ran, so a
+//! callback changing its `&mut T` changes the stored list.
+//!
+//! `FileStore` writes to a temporary file renamed over the old one, so a crash never leaves half a
+//! list behind. `AppPage` and `ItemList` lock their store while they're displayed, from loading
+//! the list until the changes of the callback are saved, so rofi instances started at the same time
+//! wait for each other instead of overwriting each other's changes. A `FileStore` takes the lock on
+//! a `.lock` file next to the list; opening the same file through another `FileStore` from a
+//! callback of the page holding the lock waits forever.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::components::ItemList;
+//! use rustofi::store::{FileStore, Format};
+//!
+//! ItemList::new(Vec::new(), Box::new(|todo: &mut String| {
+//!     *todo = format!("DONE {}", todo);
+//!     Ok(())
+//! }))
+//! .store(FileStore::new("todos", Format::Json))
+//! .display("Todos".to_string());
+//! ```
+use std::cell::RefCell;
+use std::clone::Clone;
+use std::marker::PhantomData;
+use std::rc::Rc;
+#[cfg(feature = "file-store")]
+use std::fs::{self, File, OpenOptions};
+#[cfg(feature = "file-store")]
+use std::io::{self, Write};
+#[cfg(feature = "file-store")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "file-store")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "file-store")]
+use serde::{Deserialize, Serialize};
+
+use crate::undo::{snapshot, Snapshot, UndoStack};
+#[cfg(feature = "file-store")]
+use crate::xdg::data_dir;
+
+/// storage backend of a list of `T`
+pub trait Store<T> {
+    /// read the stored list
+    fn load(&mut self) -> Result<Vec<T>, String>;
+    /// replace the stored list with `items`
+    fn save(&mut self, items: &[T]) -> Result<(), String>;
+    /// keep other processes from changing the list until `unlock`, so it can be loaded, changed and
+    /// saved back without losing their changes. Does nothing by default
+    fn lock(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+    /// release the lock taken by `lock`
+    fn unlock(&mut self) {}
+}
+
+/// the `Store` of an `AppPage` or `ItemList`, shared with the lock held while they're displayed
+/// and the undo operations recording changes to it
+pub struct SharedStore<T> {
+    pub(crate) store: Rc<RefCell<dyn Store<T>>>,
+    snapshot: Snapshot<T>
+}
+
+impl<T: 'static> SharedStore<T> {
+    /// share `store`
+    pub(crate) fn new<S: Store<T> + 'static>(store: S) -> Self {
+        let store: Rc<RefCell<dyn Store<T>>> = Rc::new(RefCell::new(store));
+        SharedStore {
+            snapshot: snapshot(&store),
+            store
+        }
+    }
+}
+
+/// holds the lock of a store, released when dropped
+pub(crate) struct StoreLock<T, S: Store<T> + ?Sized> {
+    store: Rc<RefCell<S>>,
+    item: PhantomData<T>
+}
+
+impl<T, S: Store<T> + ?Sized> StoreLock<T, S> {
+    /// lock `store` until the returned lock is dropped
+    pub(crate) fn take(store: &Rc<RefCell<S>>) -> Result<Self, String> {
+        store.borrow_mut().lock()?;
+        Ok(StoreLock {
+            store: store.clone(),
+            item: PhantomData
+        })
+    }
+}
+
+impl<T, S: Store<T> + ?Sized> Drop for StoreLock<T, S> {
+    fn drop(&mut self) {
+        if let Ok(mut store) = self.store.try_borrow_mut() {
+            store.unlock();
+        }
+    }
+}
+
+/// a `Store` keeping the list in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore<T> {
+    pub items: Vec<T>
+}
+
+impl<T> MemoryStore<T> {
+    /// create a store holding `items`
+    pub fn new(items: Vec<T>) -> Self {
+        MemoryStore { items }
+    }
+}
+
+impl<T: Clone> Store<T> for MemoryStore<T> {
+    fn load(&mut self) -> Result<Vec<T>, String> {
+        Ok(self.items.clone())
+    }
+
+    fn save(&mut self, items: &[T]) -> Result<(), String> {
+        self.items = items.to_vec();
+        Ok(())
+    }
+}
+
+/// file format of a `FileStore`
+#[cfg(feature = "file-store")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Toml
+}
+
+#[cfg(feature = "file-store")]
+impl Format {
+    /// extension of the files in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Toml => "toml"
+        }
+    }
+}
+
+/// the contents of a store file. TOML documents have to be tables, so the list is kept under
+/// `items` in both formats
+#[cfg(feature = "file-store")]
+#[derive(Serialize, Deserialize)]
+struct Document<L> {
+    items: L
+}
+
+/// a `Store` keeping the list in a file, serialized with serde
+#[cfg(feature = "file-store")]
+#[derive(Debug)]
+pub struct FileStore<T> {
+    /// file the list is stored in
+    pub path: PathBuf,
+    pub format: Format,
+    /// the lock file while `lock` holds it
+    held: Option<File>,
+    item: PhantomData<T>
+}
+
+/// clones don't share the lock of the original
+#[cfg(feature = "file-store")]
+impl<T> Clone for FileStore<T> {
+    fn clone(&self) -> Self {
+        FileStore::at(self.path.clone(), self.format)
+    }
+}
+
+#[cfg(feature = "file-store")]
+impl<T> FileStore<T> {
+    /// create a store named `name` in the rustofi data directory
+    pub fn new(name: &str, format: Format) -> Self {
+        let file = format!("{}.{}", name, format.extension());
+        FileStore::at(data_dir().join("store").join(file), format)
+    }
+
+    /// create a store kept in the file at `path`
+    pub fn at(path: PathBuf, format: Format) -> Self {
+        FileStore {
+            path,
+            format,
+            held: None,
+            item: PhantomData
+        }
+    }
+
+    /// the file locked while the store is read or written, next to the store itself
+    fn lock_file(&self) -> io::Result<File> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(with_suffix(&self.path, ".lock"))
+    }
+
+    /// read the list while holding a shared lock, unless `lock` already holds an exclusive one. A
+    /// missing file is an empty list
+    fn read(&self) -> io::Result<Option<String>> {
+        let _lock = match self.held {
+            Some(_) => None,
+            None => {
+                let lock = self.lock_file()?;
+                lock.lock_shared()?;
+                Some(lock)
+            }
+        };
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// replace the file with `contents` while holding an exclusive lock, through a temporary file
+    /// renamed over it
+    fn write(&self, contents: &str) -> io::Result<()> {
+        let _lock = match self.held {
+            Some(_) => None,
+            None => {
+                let lock = self.lock_file()?;
+                lock.lock()?;
+                Some(lock)
+            }
+        };
+        let temporary = with_suffix(&self.path, ".tmp");
+        let mut file = File::create(&temporary)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temporary, &self.path)
+    }
+}
+
+#[cfg(feature = "file-store")]
+impl<T: Serialize + DeserializeOwned> Store<T> for FileStore<T> {
+    fn load(&mut self) -> Result<Vec<T>, String> {
+        let contents = match self.read() {
+            Ok(Some(contents)) => contents,
+            Ok(None) => return Ok(Vec::new()),
+            Err(e) => return Err(format!("error reading {}: {}", self.path.display(), e))
+        };
+        let document: Result<Document<Vec<T>>, String> = match self.format {
+            Format::Json => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+            Format::Toml => toml::from_str(&contents).map_err(|e| e.to_string())
+        };
+        document
+            .map(|d| d.items)
+            .map_err(|e| format!("error parsing {}: {}", self.path.display(), e))
+    }
+
+    fn save(&mut self, items: &[T]) -> Result<(), String> {
+        let document = Document { items };
+        let contents = match self.format {
+            Format::Json => serde_json::to_string_pretty(&document).map_err(|e| e.to_string()),
+            Format::Toml => toml::to_string(&document).map_err(|e| e.to_string())
+        };
+        let contents = contents.map_err(|e| format!("error serializing the list: {}", e))?;
+        self.write(&contents)
+            .map_err(|e| format!("error writing {}: {}", self.path.display(), e))
+    }
+
+    fn lock(&mut self) -> Result<(), String> {
+        if self.held.is_some() {
+            return Ok(());
+        }
+        let lock = self
+            .lock_file()
+            .and_then(|lock| lock.lock().map(|_| lock))
+            .map_err(|e| format!("error locking {}: {}", self.path.display(), e))?;
+        self.held = Some(lock);
+        Ok(())
+    }
+
+    fn unlock(&mut self) {
+        // closing the lock file releases the lock
+        self.held = None;
+    }
+}
+
+/// `path` with `suffix` appended to its file name
+#[cfg(feature = "file-store")]
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// lock `store`, when there is one, and replace `items` with the list kept in it. The store stays
+/// locked until the returned lock is dropped
+pub(crate) fn load_items<T>(
+    store: &Option<SharedStore<T>>, items: &mut Vec<T>
+) -> Result<Option<StoreLock<T, dyn Store<T>>>, String> {
+    let store = match store {
+        Some(store) => &store.store,
+        None => return Ok(None)
+    };
+    let lock = StoreLock::take(store)?;
+    *items = store.borrow_mut().load()?;
+    Ok(Some(lock))
+}
+
+/// save `items` back to `store`, when there is one. `order` is the position in the stored list of
+/// each item, as they may be shown in another order
+pub(crate) fn save_items<T: Clone>(
+    store: &Option<SharedStore<T>>, items: &[T], order: &[usize]
+) -> Result<(), String> {
+    match store {
+        Some(store) => store.store.borrow_mut().save(&in_store_order(items, order)),
+        None => Ok(())
+    }
+}
+
+/// save `items` like `save_items`, recording the change as `label` in `undo` if there is one
+pub(crate) fn save_change<T: Clone>(
+    store: &Option<SharedStore<T>>, items: &[T], order: &[usize], undo: &Option<UndoStack>,
+    label: &str
+) -> Result<(), String> {
+    let (store, undo) = match (store, undo) {
+        (Some(store), Some(undo)) => (store, undo),
+        _ => return save_items(store, items, order)
+    };
+    let before = store.store.borrow_mut().load()?;
+    let after = in_store_order(items, order);
+    store.store.borrow_mut().save(&after)?;
+    undo.record((store.snapshot)(label, before, after));
+    Ok(())
+}
+
+/// `items` put back in the order of the store, `order` being the position of each in it
+fn in_store_order<T: Clone>(items: &[T], order: &[usize]) -> Vec<T> {
+    let mut stored = items.to_vec();
+    for (item, position) in items.iter().zip(order) {
+        stored[*position] = item.clone();
+    }
+    stored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usage::{sort_items, UsageTracker};
+
+    /// a fresh path under the temp dir
+    #[cfg(feature = "file-store")]
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!("rustofi-store-{}", std::process::id()))
+            .join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[cfg(feature = "file-store")]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Todo {
+        task: String,
+        done: bool
+    }
+
+    #[cfg(feature = "file-store")]
+    fn todos() -> Vec<Todo> {
+        vec![
+            Todo { task: "milk".to_string(), done: false },
+            Todo { task: "eggs".to_string(), done: true },
+        ]
+    }
+
+    #[cfg(feature = "file-store")]
+    #[test]
+    fn json_round_trip() {
+        let mut store = FileStore::at(temp_path("todos.json"), Format::Json);
+        store.save(&todos()).unwrap();
+        assert_eq!(store.load().unwrap(), todos());
+    }
+
+    #[cfg(feature = "file-store")]
+    #[test]
+    fn toml_round_trip() {
+        let mut store = FileStore::at(temp_path("todos.toml"), Format::Toml);
+        store.save(&todos()).unwrap();
+        assert_eq!(store.load().unwrap(), todos());
+    }
+
+    #[cfg(feature = "file-store")]
+    #[test]
+    fn missing_file_is_an_empty_list() {
+        let mut store: FileStore<Todo> = FileStore::at(temp_path("missing.json"), Format::Json);
+        assert_eq!(store.load().unwrap(), Vec::new());
+    }
+
+    #[cfg(feature = "file-store")]
+    #[test]
+    fn parse_error_is_an_error() {
+        let path = temp_path("broken.toml");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "items = [").unwrap();
+        let mut store: FileStore<Todo> = FileStore::at(path, Format::Toml);
+        assert!(store.load().unwrap_err().starts_with("error parsing"));
+    }
+
+    #[cfg(feature = "file-store")]
+    #[test]
+    fn locked_store_can_still_be_read_and_written() {
+        let mut store = FileStore::at(temp_path("locked.json"), Format::Json);
+        store.lock().unwrap();
+        store.save(&todos()).unwrap();
+        assert_eq!(store.load().unwrap(), todos());
+        store.unlock();
+        assert!(store.held.is_none());
+    }
+
+    #[test]
+    fn save_items_puts_items_back_in_store_order() {
+        let usage_path = std::env::temp_dir()
+            .join(format!("rustofi-store-{}", std::process::id()))
+            .join("usage");
+        let _ = std::fs::remove_file(&usage_path);
+        let usage = Some(UsageTracker::at(usage_path));
+        usage.as_ref().unwrap().record("c").unwrap();
+
+        let store = Some(SharedStore::new(MemoryStore::new(vec!["a", "b", "c"])));
+        let mut items = Vec::new();
+        let lock = load_items(&store, &mut items).unwrap();
+        assert!(lock.is_some());
+        let order = sort_items(&usage, &mut items);
+        assert_eq!(items, vec!["c", "a", "b"]);
+        items[0] = "C";
+        save_items(&store, &items, &order).unwrap();
+        let stored = store.as_ref().unwrap().store.borrow_mut().load().unwrap();
+        assert_eq!(stored, vec!["a", "b", "C"]);
+    }
+
+    #[test]
+    fn save_change_records_an_undoable_change() {
+        let store = Some(SharedStore::new(MemoryStore::new(vec!["a", "b"])));
+        let undo = UndoStack::new();
+        let stored = || store.as_ref().unwrap().store.borrow_mut().load().unwrap();
+        save_change(&store, &["b", "A"], &[1, 0], &Some(undo.clone()), "edit a").unwrap();
+        assert_eq!(stored(), vec!["A", "b"]);
+        assert_eq!(undo.undo(), Ok(Some("edit a".to_string())));
+        assert_eq!(stored(), vec!["a", "b"]);
+        undo.redo().unwrap();
+        assert_eq!(stored(), vec!["A", "b"]);
+    }
+}
@@ -0,0 +1,192 @@
+//! A `TreeMenu` walks a hierarchy of menus in a single window instead of needing a state per
+//! level in the application's state machine. Selecting a branch shows its children, `[back]` or
+//! Escape go up a level and the prompt shows the path taken so far. Selecting a leaf runs the
+//! callback with the whole path from the root down to that leaf.
+//!
+//! The hierarchy is anything implementing `TreeNode`, or a plain `Tree` built by hand.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::tree::{Tree, TreeMenu};
+//!
+//! let settings = vec![
+//!     Tree::branch("Display", vec![Tree::leaf("Brightness"), Tree::leaf("Night light")]),
+//!     Tree::branch("Sound", vec![Tree::leaf("Volume"), Tree::leaf("Output")]),
+//! ];
+//! TreeMenu::new(
+//!     settings,
+//!     Box::new(|path: &[Tree<&str>]| {
+//!         let names: Vec<&str> = path.iter().map(|node| node.value).collect();
+//!         println!("open {}", names.join("/"));
+//!         Ok(())
+//!     })
+//! )
+//! .display("Settings".to_string());
+//! ```
+use std::fmt::{self, Display};
+
+use crate::guard::guarded;
+use crate::labels::{BuiltIn, Labels};
+use crate::window::{Location, Response, Window};
+use crate::{CallbackResult, RustofiResult};
+
+/// a node of a hierarchy shown by a `TreeMenu`, a leaf when it has no children
+pub trait TreeNode: Display + Clone {
+    /// the nodes one level below this one
+    fn children(&self) -> Vec<Self>;
+}
+
+/// a hand built hierarchy of values
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tree<T> {
+    pub value: T,
+    pub children: Vec<Tree<T>>
+}
+
+impl<T> Tree<T> {
+    /// create a node without children
+    pub fn leaf(value: T) -> Self {
+        Tree {
+            value,
+            children: Vec::new()
+        }
+    }
+
+    /// create a node holding `children`
+    pub fn branch(value: T, children: Vec<Tree<T>>) -> Self {
+        Tree { value, children }
+    }
+}
+
+impl<T: Display> Display for Tree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<T: Display + Clone> TreeNode for Tree<T> {
+    fn children(&self) -> Vec<Self> {
+        self.children.clone()
+    }
+}
+
+/// callback run with the path from a root down to the selected leaf
+pub type LeafCallback<T> = Box<dyn FnMut(&[T]) -> CallbackResult>;
+
+/// `TreeMenu` shows one level of a hierarchy at a time, starting from its roots. Branches are
+/// marked with a trailing `›` and descend when selected, leaves run `leaf_callback`
+pub struct TreeMenu<'a, T> {
+    pub roots: Vec<T>,
+    pub leaf_callback: LeafCallback<T>,
+    /// text put between the levels of the path shown in the prompt
+    pub breadcrumb_separator: String,
+    pub labels: Option<Labels>,
+    pub window: Window<'a>
+}
+
+impl<'a, T: TreeNode> TreeMenu<'a, T> {
+    /// create a menu over the hierarchy below `roots`
+    pub fn new(roots: Vec<T>, leaf_callback: LeafCallback<T>) -> Self {
+        TreeMenu {
+            roots,
+            leaf_callback,
+            breadcrumb_separator: " › ".to_string(),
+            labels: None,
+            window: TreeMenu::<T>::create_window()
+        }
+    }
+
+    /// create a rofi window in the middle of the screen
+    fn create_window() -> Window<'a> {
+        Window::new("Tree")
+            .format('s')
+            .location(Location::MiddleCentre)
+    }
+
+    /// set the text put between the levels of the path shown in the prompt
+    pub fn breadcrumb_separator(mut self, separator: &str) -> Self {
+        self.breadcrumb_separator = separator.to_string();
+        self
+    }
+
+    /// set a completely custom window
+    pub fn window(mut self, window: Window<'a>) -> Self {
+        self.window = window.format('s');
+        self
+    }
+
+    /// set the labels of the built-in entries, overriding the global ones
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// walk the hierarchy until a leaf is selected, returning the path to it joined with the
+    /// breadcrumb separator. Escape at the top level or `[cancel]` return `Cancel`
+    pub fn display(&mut self, prompt: String) -> RustofiResult {
+        let labels = self.labels.clone().unwrap_or_else(Labels::global);
+        let mut path: Vec<T> = Vec::new();
+        loop {
+            let nodes = match path.last() {
+                Some(node) => node.children(),
+                None => self.roots.clone()
+            };
+            let branches: Vec<bool> = nodes.iter().map(|n| !n.children().is_empty()).collect();
+            let extra = match path.is_empty() {
+                true => labels.rows(&[BuiltIn::Separator, BuiltIn::Cancel]),
+                false => labels.rows(&[BuiltIn::Separator, BuiltIn::Back, BuiltIn::Cancel])
+            };
+            let mut display_options: Vec<String> = nodes
+                .iter()
+                .zip(&branches)
+                .map(|(node, branch)| match branch {
+                    true => format!("{} ›", node),
+                    false => node.to_string()
+                })
+                .collect();
+            display_options.extend(extra.iter().map(|(_, label)| label.clone()));
+            let breadcrumbs = self.breadcrumbs(&prompt, &path);
+            let response = self
+                .window
+                .clone()
+                .lines(display_options.len() as i32)
+                .prompt(breadcrumbs.clone())
+                .select(display_options);
+            match response {
+                Ok(Response::Row(row)) if row < nodes.len() => {
+                    path.push(nodes[row].clone());
+                    if branches[row] {
+                        continue;
+                    }
+                    let (callback, path) = (&mut self.leaf_callback, &path);
+                    return match guarded(|| callback(path)) {
+                        Ok(_) => RustofiResult::Selection(self.breadcrumbs("", path)),
+                        Err(m) => RustofiResult::Error(m)
+                    };
+                }
+                Ok(Response::Row(row)) => match extra.get(row - nodes.len()).map(|(b, _)| *b) {
+                    Some(BuiltIn::Back) => {
+                        path.pop();
+                    }
+                    Some(BuiltIn::Cancel) => return RustofiResult::Cancel,
+                    _ => {}
+                },
+                Ok(Response::Entry(input)) => return RustofiResult::Selection(input),
+                Ok(_) if !path.is_empty() => {
+                    path.pop();
+                }
+                Ok(_) => return RustofiResult::Cancel,
+                Err(_) => {
+                    return RustofiResult::Error("error getting user input from rofi".to_string())
+                }
+            }
+        }
+    }
+
+    /// `root` followed by the nodes of `path`, joined by the breadcrumb separator
+    fn breadcrumbs(&self, root: &str, path: &[T]) -> String {
+        let levels = Some(root.to_string()).filter(|r| !r.is_empty()).into_iter();
+        let levels: Vec<String> = levels.chain(path.iter().map(|n| n.to_string())).collect();
+        levels.join(&self.breadcrumb_separator)
+    }
+}
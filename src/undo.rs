@@ -0,0 +1,371 @@
+//! An `UndoStack` records reversible operations so mistakes made through the UI, like deleting or
+//! toggling the wrong item, can be taken back. Each `Operation` knows how to undo and redo itself
+//! instead of snapshotting the data, so it works with data living anywhere: a database, a file or
+//! a global.
+//!
+//! Operations are recorded by the application, typically in the callbacks of `AppPage`,
+//! `ItemList` and `ActionList` or after an `EntryBox` edit. Giving the stack to one of those
+//! components with their `undo` method adds `[undo]` and `[redo]` entries while there is
+//! something to undo or redo, and the keybindings set with `UndoStack::keybindings`. Undoing
+//! closes the window returning `RustofiResult::Undone`, so the application shows its now
+//! changed data again.
+//!
+//! Changes the components make to a list kept in a `Store` are recorded by themselves: the edits,
+//! moves, deletions and additions of a `ListEditor`, and the `InlineEdit`s of an `AppPage` or
+//! `ItemList` with a store along with item callbacks and item menus changing an item's text.
+//! Undoing one saves the list as it was before the change.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::components::ItemList;
+//! use rustofi::undo::{Operation, UndoStack};
+//! use rustofi::RustofiResult;
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! let todos = Rc::new(RefCell::new(vec!["milk".to_string(), "eggs".to_string()]));
+//! let stack = UndoStack::new().keybindings("Control+z", "Control+y");
+//! loop {
+//!     let (list, history) = (todos.clone(), stack.clone());
+//!     let result = ItemList::new(todos.borrow().clone(), Box::new(move |item: &mut String| {
+//!         let (undo_list, redo_list, item) = (list.clone(), list.clone(), item.clone());
+//!         let position = list.borrow().iter().position(|t| *t == item).unwrap();
+//!         history.apply(Operation::new(
+//!             &format!("delete {}", item),
+//!             Box::new(move || Ok(undo_list.borrow_mut().insert(position, item.clone()))),
+//!             Box::new(move || Ok(drop(redo_list.borrow_mut().remove(position))))
+//!         ))
+//!     }))
+//!     .undo(stack.clone())
+//!     .display("Delete a todo".to_string());
+//!     match result {
+//!         RustofiResult::Undone(_) | RustofiResult::Redone(_) | RustofiResult::Selection(_) => {}
+//!         _ => break
+//!     }
+//! }
+//! ```
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::guard::guarded;
+use crate::labels::BuiltIn;
+use crate::store::Store;
+use crate::{CallbackResult, RustofiResult};
+
+/// one direction of an `Operation`
+pub type OperationStep = Box<dyn FnMut() -> CallbackResult>;
+/// builds the operation changing a stored list from the first list given to the second one
+pub(crate) type Snapshot<T> = Box<dyn Fn(&str, Vec<T>, Vec<T>) -> Operation>;
+
+/// a change that can be undone and redone
+pub struct Operation {
+    /// short description of the change, returned when it's undone or redone
+    pub label: String,
+    undo: OperationStep,
+    redo: OperationStep
+}
+
+impl Operation {
+    /// create an operation taken back by `undo` and done again by `redo`
+    pub fn new(label: &str, undo: OperationStep, redo: OperationStep) -> Self {
+        Operation {
+            label: label.to_string(),
+            undo,
+            redo
+        }
+    }
+
+    /// create an operation that is its own inverse, like toggling a flag
+    pub fn toggle(label: &str, step: OperationStep) -> Self {
+        let step = Rc::new(RefCell::new(step));
+        let undo = step.clone();
+        Operation::new(
+            label,
+            Box::new(move || (undo.borrow_mut())()),
+            Box::new(move || (step.borrow_mut())())
+        )
+    }
+}
+
+struct History {
+    done: Vec<Operation>,
+    undone: Vec<Operation>,
+    limit: usize
+}
+
+impl History {
+    /// the operations to redo, or the ones to undo
+    fn stack(&mut self, undone: bool) -> &mut Vec<Operation> {
+        match undone {
+            true => &mut self.undone,
+            false => &mut self.done
+        }
+    }
+}
+
+/// an operation taken off its stack while one of its steps runs. It goes back on the stack if the
+/// step fails or panics
+struct Pending<'h> {
+    history: &'h RefCell<History>,
+    redo: bool,
+    operation: Option<Operation>
+}
+
+impl<'h> Drop for Pending<'h> {
+    fn drop(&mut self) {
+        if let (Some(operation), Ok(mut history)) =
+            (self.operation.take(), self.history.try_borrow_mut())
+        {
+            history.stack(self.redo).push(operation);
+        }
+    }
+}
+
+/// undo and redo history shared between an application's callbacks and its components. Clones
+/// share the same history
+#[derive(Clone)]
+pub struct UndoStack {
+    history: Rc<RefCell<History>>,
+    /// rofi key combinations undoing and redoing
+    pub keybindings: Option<(String, String)>
+}
+
+impl UndoStack {
+    /// create an empty history remembering up to 100 operations
+    pub fn new() -> Self {
+        UndoStack {
+            history: Rc::new(RefCell::new(History {
+                done: Vec::new(),
+                undone: Vec::new(),
+                limit: 100
+            })),
+            keybindings: None
+        }
+    }
+
+    /// set the number of operations remembered, the oldest are forgotten first
+    pub fn limit(self, limit: usize) -> Self {
+        self.history.borrow_mut().limit = limit;
+        self
+    }
+
+    /// set the key combinations undoing and redoing in components showing the stack
+    pub fn keybindings(mut self, undo: &str, redo: &str) -> Self {
+        self.keybindings = Some((undo.to_string(), redo.to_string()));
+        self
+    }
+
+    /// record an operation that was already carried out. Anything undone before can't be redone
+    /// anymore
+    pub fn record(&self, operation: Operation) {
+        let mut history = self.history.borrow_mut();
+        history.done.push(operation);
+        history.undone.clear();
+        let excess = history.done.len().saturating_sub(history.limit);
+        history.done.drain(..excess);
+    }
+
+    /// carry out `operation` by running its redo step, then record it
+    pub fn apply(&self, mut operation: Operation) -> CallbackResult {
+        (operation.redo)()?;
+        self.record(operation);
+        Ok(())
+    }
+
+    /// whether there is an operation to undo
+    pub fn can_undo(&self) -> bool {
+        !self.history.borrow().done.is_empty()
+    }
+
+    /// whether there is an operation to redo
+    pub fn can_redo(&self) -> bool {
+        !self.history.borrow().undone.is_empty()
+    }
+
+    /// undo the latest operation, returning its label. `None` if there was nothing to undo. A
+    /// failing undo stays on the stack
+    pub fn undo(&self) -> Result<Option<String>, String> {
+        self.step(false)
+    }
+
+    /// redo the latest undone operation, returning its label. `None` if there was nothing to redo
+    pub fn redo(&self) -> Result<Option<String>, String> {
+        self.step(true)
+    }
+
+    /// run the undo or redo step of the latest operation, moving it over to the other stack
+    fn step(&self, redo: bool) -> Result<Option<String>, String> {
+        // steps may record operations themselves, so the history isn't borrowed while they run
+        let operation = self.history.borrow_mut().stack(redo).pop();
+        let mut pending = Pending { history: &self.history, redo, operation };
+        let operation = match &mut pending.operation {
+            Some(operation) => operation,
+            None => return Ok(None)
+        };
+        match redo {
+            true => (operation.redo)()?,
+            false => (operation.undo)()?
+        }
+        let operation = pending.operation.take().expect("the operation is still pending");
+        let label = operation.label.clone();
+        self.history.borrow_mut().stack(!redo).push(operation);
+        Ok(Some(label))
+    }
+
+    /// forget every operation
+    pub fn clear(&self) {
+        let mut history = self.history.borrow_mut();
+        history.done.clear();
+        history.undone.clear();
+    }
+
+    /// undo or redo, building the matching result
+    pub(crate) fn trigger<A>(&self, redo: bool) -> RustofiResult<A> {
+        let result = match redo {
+            true => guarded(|| self.redo()),
+            false => guarded(|| self.undo())
+        };
+        match (result, redo) {
+            (Ok(Some(label)), true) => RustofiResult::Redone(label),
+            (Ok(Some(label)), false) => RustofiResult::Undone(label),
+            (Ok(None), _) => RustofiResult::Cancel,
+            (Err(m), _) => RustofiResult::Error(m)
+        }
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        UndoStack::new()
+    }
+}
+
+/// the snapshot of `store`, undoing a change by saving the list from before it and redoing it by
+/// saving the list from after it
+pub(crate) fn snapshot<T: 'static, S: Store<T> + ?Sized + 'static>(
+    store: &Rc<RefCell<S>>
+) -> Snapshot<T> {
+    let store = store.clone();
+    Box::new(move |label, before, after| {
+        let (undo_store, redo_store) = (store.clone(), store.clone());
+        Operation::new(
+            label,
+            Box::new(move || undo_store.borrow_mut().save(&before)),
+            Box::new(move || redo_store.borrow_mut().save(&after))
+        )
+    })
+}
+
+/// the `[undo]` and `[redo]` rows to show for `undo`, only those with something to do
+pub(crate) fn undo_rows(undo: &Option<UndoStack>) -> Vec<BuiltIn> {
+    match undo {
+        Some(undo) => {
+            let undo_row = Some(BuiltIn::Undo).filter(|_| undo.can_undo());
+            let redo_row = Some(BuiltIn::Redo).filter(|_| undo.can_redo());
+            undo_row.into_iter().chain(redo_row).collect()
+        }
+        None => Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    /// an operation adding `amount` to `value` when redone
+    fn add(value: &Rc<RefCell<i32>>, amount: i32) -> Operation {
+        let (undo_value, redo_value) = (value.clone(), value.clone());
+        Operation::new(
+            &format!("add {}", amount),
+            Box::new(move || {
+                *undo_value.borrow_mut() -= amount;
+                Ok(())
+            }),
+            Box::new(move || {
+                *redo_value.borrow_mut() += amount;
+                Ok(())
+            })
+        )
+    }
+
+    #[test]
+    fn undo_and_redo_run_the_steps_in_order() {
+        let (stack, value) = (UndoStack::new(), Rc::new(RefCell::new(0)));
+        stack.apply(add(&value, 1)).unwrap();
+        stack.apply(add(&value, 10)).unwrap();
+        assert_eq!(*value.borrow(), 11);
+        assert_eq!(stack.undo(), Ok(Some("add 10".to_string())));
+        assert_eq!(stack.undo(), Ok(Some("add 1".to_string())));
+        assert_eq!(stack.undo(), Ok(None));
+        assert_eq!(*value.borrow(), 0);
+        assert_eq!(stack.redo(), Ok(Some("add 1".to_string())));
+        assert_eq!(*value.borrow(), 1);
+        assert!(stack.can_undo() && stack.can_redo());
+    }
+
+    #[test]
+    fn recording_clears_the_redo_stack() {
+        let (stack, value) = (UndoStack::new(), Rc::new(RefCell::new(0)));
+        stack.apply(add(&value, 1)).unwrap();
+        stack.undo().unwrap();
+        assert!(stack.can_redo());
+        stack.apply(add(&value, 2)).unwrap();
+        assert!(!stack.can_redo());
+        assert_eq!(stack.redo(), Ok(None));
+    }
+
+    #[test]
+    fn limit_forgets_the_oldest_operations() {
+        let (stack, value) = (UndoStack::new().limit(2), Rc::new(RefCell::new(0)));
+        for amount in 1..=3 {
+            stack.apply(add(&value, amount)).unwrap();
+        }
+        assert_eq!(stack.undo(), Ok(Some("add 3".to_string())));
+        assert_eq!(stack.undo(), Ok(Some("add 2".to_string())));
+        assert_eq!(stack.undo(), Ok(None));
+        assert_eq!(*value.borrow(), 1);
+    }
+
+    #[test]
+    fn toggle_is_its_own_inverse() {
+        let (stack, flag) = (UndoStack::new(), Rc::new(RefCell::new(false)));
+        let toggled = flag.clone();
+        let step = Box::new(move || {
+            let value = !*toggled.borrow();
+            *toggled.borrow_mut() = value;
+            Ok(())
+        });
+        stack.apply(Operation::toggle("toggle", step)).unwrap();
+        assert!(*flag.borrow());
+        stack.undo().unwrap();
+        assert!(!*flag.borrow());
+    }
+
+    #[test]
+    fn failing_or_panicking_steps_stay_on_the_stack() {
+        let stack = UndoStack::new();
+        stack.record(Operation::new(
+            "fails",
+            Box::new(|| Err("can't".to_string())),
+            Box::new(|| Ok(()))
+        ));
+        assert_eq!(stack.undo(), Err("can't".to_string()));
+        assert!(stack.can_undo());
+        stack.clear();
+        stack.record(Operation::new("panics", Box::new(|| panic!("boom")), Box::new(|| Ok(()))));
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| stack.undo())).is_err());
+        assert!(stack.can_undo() && !stack.can_redo());
+    }
+
+    #[test]
+    fn undo_rows_show_only_what_can_be_done() {
+        let (stack, value) = (UndoStack::new(), Rc::new(RefCell::new(0)));
+        assert!(undo_rows(&Some(stack.clone())).is_empty());
+        stack.apply(add(&value, 1)).unwrap();
+        assert_eq!(undo_rows(&Some(stack.clone())), vec![BuiltIn::Undo]);
+        stack.undo().unwrap();
+        assert_eq!(undo_rows(&Some(stack)), vec![BuiltIn::Redo]);
+        assert!(undo_rows(&None).is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+//! A `UsageTracker` remembers which items of a page get selected so `AppPage` and `ItemList` can
+//! show the ones used most often and most recently first. Each selection adds one to the item's
+//! score, and scores lose half their weight every `half_life`, so an item picked a lot last month
+//! ends up below one picked a few times today.
+//!
+//! Scores are stored per page id in a plain text file under `$XDG_DATA_HOME/rustofi/usage`, one
+//! `score<TAB>timestamp<TAB>item` line per item. Items are told apart by their `to_string()`.
+//!
+//! # Example
+//! ```no_run
+//! use rustofi::components::ItemList;
+//! use rustofi::usage::UsageTracker;
+//!
+//! let apps = vec!["firefox".to_string(), "thunderbird".to_string(), "htop".to_string()];
+//! ItemList::new(apps, Box::new(|_| Ok(())))
+//!     .usage(UsageTracker::new("launcher"))
+//!     .display("Launch".to_string());
+//! ```
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::xdg::data_dir;
+
+/// stored score of an item, as of `updated` (seconds since the epoch)
+#[derive(Debug, Clone, Copy)]
+struct Usage {
+    score: f64,
+    updated: u64
+}
+
+/// persisted frecency scores of the items of one page
+#[derive(Debug, Clone)]
+pub struct UsageTracker {
+    /// file the scores are stored in
+    pub path: PathBuf,
+    /// time after which a selection only counts for half
+    pub half_life: Duration,
+    /// maximum number of items remembered, the lowest scores are dropped first
+    pub limit: usize
+}
+
+impl UsageTracker {
+    /// create a tracker for the page `id` stored in the rustofi data directory
+    pub fn new(id: &str) -> Self {
+        UsageTracker::at(data_dir().join("usage").join(id))
+    }
+
+    /// create a tracker stored in the file at `path`
+    pub fn at(path: PathBuf) -> Self {
+        UsageTracker {
+            path,
+            half_life: Duration::from_secs(7 * 24 * 60 * 60),
+            limit: 500
+        }
+    }
+
+    /// set the time after which a selection only counts for half, a week by default
+    pub fn half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// set the maximum number of items remembered
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// the current score of every remembered item
+    pub fn scores(&self) -> HashMap<String, f64> {
+        let now = now();
+        self.read()
+            .into_iter()
+            .map(|(item, usage)| (item, self.decayed(usage, now)))
+            .collect()
+    }
+
+    /// the current score of `item`, 0 if it was never selected
+    pub fn score(&self, item: &str) -> f64 {
+        self.scores().get(&key(item)).copied().unwrap_or(0.0)
+    }
+
+    /// sort `items` by score, highest first. Items with equal scores keep their order
+    pub fn sort<T: Display>(&self, items: &mut [T]) {
+        self.sort_by_label(items, |item| item.to_string());
+    }
+
+    /// sort `items` by the score of the label `label` gives them, highest first
+    fn sort_by_label<X, F: Fn(&X) -> String>(&self, items: &mut [X], label: F) {
+        let scores = self.scores();
+        let score = |item: &X| scores.get(&key(&label(item))).copied().unwrap_or(0.0);
+        // labels are built once per item instead of once per comparison. Scores are never
+        // negative, so their bits sort the same way they do
+        items.sort_by_cached_key(|item| Reverse(score(item).to_bits()));
+    }
+
+    /// record a selection of `item`
+    pub fn record(&self, item: &str) -> io::Result<()> {
+        let now = now();
+        let item = key(item);
+        let mut usages = self.read();
+        let score = usages
+            .get(&item)
+            .map_or(0.0, |usage| self.decayed(*usage, now));
+        usages.insert(item, Usage { score: score + 1.0, updated: now });
+        self.write(usages)
+    }
+
+    /// stop remembering `item`
+    pub fn forget(&self, item: &str) -> io::Result<()> {
+        let mut usages = self.read();
+        usages.remove(&key(item));
+        self.write(usages)
+    }
+
+    /// forget every item
+    pub fn reset(&self) -> io::Result<()> {
+        self.write(HashMap::new())
+    }
+
+    /// score of `usage` decayed from its last update until `now`
+    fn decayed(&self, usage: Usage, now: u64) -> f64 {
+        let age = now.saturating_sub(usage.updated) as f64;
+        let half_life = self.half_life.as_secs_f64().max(1.0);
+        usage.score * 0.5f64.powf(age / half_life)
+    }
+
+    /// read the stored scores, skipping malformed lines. A missing file has no scores
+    fn read(&self) -> HashMap<String, Usage> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(_) => return HashMap::new()
+        };
+        contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let score = fields.next()?.parse().ok()?;
+                let updated = fields.next()?.parse().ok()?;
+                let item = fields.next()?.to_string();
+                Some((item, Usage { score, updated }))
+            })
+            .collect()
+    }
+
+    fn write(&self, usages: HashMap<String, Usage>) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let now = now();
+        let mut usages: Vec<(String, Usage, f64)> = usages
+            .into_iter()
+            .map(|(item, usage)| {
+                let score = self.decayed(usage, now);
+                (item, usage, score)
+            })
+            .collect();
+        usages.sort_by(|a, b| b.2.total_cmp(&a.2));
+        usages.truncate(self.limit);
+        let contents: String = usages
+            .iter()
+            .map(|(item, usage, _)| format!("{}\t{}\t{}\n", usage.score, usage.updated, item))
+            .collect();
+        fs::write(&self.path, contents)
+    }
+}
+
+/// the key `item` is remembered under: its label without newlines, which would split its line of
+/// the file
+fn key(item: &str) -> String {
+    item.replace('\n', "")
+}
+
+/// seconds since the epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// record a selection of `item` with `usage` if the component has a tracker
+pub(crate) fn record_selection(usage: &Option<UsageTracker>, item: &str) -> Result<(), String> {
+    match usage {
+        Some(usage) => usage
+            .record(item)
+            .map_err(|e| format!("error saving usage: {}", e)),
+        None => Ok(())
+    }
+}
+
+/// sort `items` by score if there's a `usage`, returning the position each item had before
+pub(crate) fn sort_items<T: Display>(
+    usage: &Option<UsageTracker>, items: &mut Vec<T>
+) -> Vec<usize> {
+    let usage = match usage {
+        Some(usage) => usage,
+        None => return (0..items.len()).collect()
+    };
+    let mut indexed: Vec<(usize, T)> = items.drain(..).enumerate().collect();
+    usage.sort_by_label(&mut indexed, |(_, item)| item.to_string());
+    let (order, sorted) = indexed.into_iter().unzip();
+    *items = sorted;
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a tracker stored in a fresh file under the temp dir
+    fn tracker(name: &str) -> UsageTracker {
+        let path = std::env::temp_dir()
+            .join(format!("rustofi-usage-{}", std::process::id()))
+            .join(name);
+        let _ = fs::remove_file(&path);
+        UsageTracker::at(path)
+    }
+
+    #[test]
+    fn record_adds_one_per_selection() {
+        let usage = tracker("record");
+        assert_eq!(usage.score("a"), 0.0);
+        usage.record("a").unwrap();
+        usage.record("a").unwrap();
+        assert!((usage.score("a") - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn scores_halve_every_half_life() {
+        let usage = tracker("decay").half_life(Duration::from_secs(100));
+        fs::create_dir_all(usage.path.parent().unwrap()).unwrap();
+        fs::write(&usage.path, format!("4\t{}\told\n", now() - 100)).unwrap();
+        assert!((usage.score("old") - 2.0).abs() < 0.05);
+        // recording adds to the decayed score
+        usage.record("old").unwrap();
+        assert!((usage.score("old") - 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn sort_puts_highest_scores_first_and_keeps_ties_in_order() {
+        let usage = tracker("sort");
+        usage.record("b").unwrap();
+        usage.record("b").unwrap();
+        usage.record("d").unwrap();
+        let mut items = vec!["a", "b", "c", "d", "e"];
+        usage.sort(&mut items);
+        assert_eq!(items, vec!["b", "d", "a", "c", "e"]);
+    }
+
+    #[test]
+    fn sort_items_returns_the_original_positions() {
+        let usage = Some(tracker("sort_items"));
+        usage.as_ref().unwrap().record("c").unwrap();
+        let mut items = vec!["a", "b", "c"];
+        assert_eq!(sort_items(&usage, &mut items), vec![2, 0, 1]);
+        assert_eq!(items, vec!["c", "a", "b"]);
+        assert_eq!(sort_items(&None, &mut items), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn limit_drops_the_lowest_scores() {
+        let usage = tracker("limit").limit(2);
+        // scores never tie when the limit is reached, ties are dropped in no particular order
+        for item in &["c", "c", "c", "b", "b", "a"] {
+            usage.record(item).unwrap();
+        }
+        let scores = usage.scores();
+        assert_eq!(scores.len(), 2);
+        assert!(scores.contains_key("b") && scores.contains_key("c"));
+    }
+
+    #[test]
+    fn labels_with_newlines_find_their_usage() {
+        let usage = tracker("newline");
+        usage.record("two\nlines").unwrap();
+        assert!((usage.score("two\nlines") - 1.0).abs() < 0.01);
+        let mut items = vec!["one".to_string(), "two\nlines".to_string()];
+        usage.sort(&mut items);
+        assert_eq!(items[0], "two\nlines");
+        usage.forget("two\nlines").unwrap();
+        assert!(usage.scores().is_empty());
+    }
+
+    #[test]
+    fn forget_and_reset_remove_scores() {
+        let usage = tracker("forget");
+        usage.record("a").unwrap();
+        usage.record("b").unwrap();
+        usage.forget("a").unwrap();
+        assert_eq!(usage.score("a"), 0.0);
+        assert!(usage.score("b") > 0.0);
+        usage.reset().unwrap();
+        assert!(usage.scores().is_empty());
+    }
+}
@@ -23,16 +23,19 @@
 //!
 //! fn main() {
 //!     // create a window with 8 lines and a vector of strings and show it
-//!     Window::new("FizzBuzz in Rofi!").lines(8).show(fizzbuzz());
+//!     let _ = Window::new("FizzBuzz in Rofi!").lines(8).show(fizzbuzz());
 //! }
 //! ```
 
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::io::{self, BufWriter, Read, Write};
 use std::str;
 
 use num_derive::ToPrimitive;
 use num_traits::ToPrimitive;
 
-use subprocess::{Popen, PopenConfig, Redirection};
+use subprocess::{ExitStatus, Popen, PopenConfig, PopenError, Redirection};
 
 use crate::errors::*;
 
@@ -93,7 +96,7 @@ pub struct Window<'m> {
     /// message to display next to the entry field
     pub prompt: String,
     /// short message displayed beneath this field and above all options
-    pub message: Option<&'m str>,
+    pub message: Option<Cow<'m, str>>,
     /// Additional args to pass to rofi
     pub additional_args: Vec<String>,
     /// location on screen to place the window
@@ -114,35 +117,74 @@ pub enum ReturnFormat {
     /// Return raw entry from the user
     StringReturn,
     /// Return an integer representing the index in the list selected
-    IntReturn
+    IntReturn,
+    /// Return the index selected followed by the text typed by the user, used by `Window::select`
+    IndexFilterReturn
 }
 
-impl<'a, 's, 'm> Window<'m> {
+/// what the user did in a window shown with `Window::select`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    /// the option at this index was selected
+    Row(usize),
+    /// text matching no option was entered
+    Entry(String),
+    /// custom keybinding `-kb-custom-<key>` was pressed, with the option at `row` highlighted and
+    /// `filter` typed in the entry field
+    Key {
+        key: usize,
+        row: Option<usize>,
+        filter: String
+    },
+    /// the window was closed without selecting anything
+    Cancel
+}
+
+impl<'a, 'm> Window<'m> {
     /// open a subprocess calling the constructed rofi command and block until it returns
-    fn run_blocking(self, options: Vec<String>) -> Result<String, WindowError> {
+    fn run_blocking<I>(self, options: I) -> Result<String, WindowError>
+    where
+        I: IntoIterator,
+        I::Item: Display
+    {
+        self.run_with_status(options).map(|(entry, _code)| entry)
+    }
+
+    /// like `run_blocking` but also return rofi's exit code
+    fn run_with_status<I>(self, options: I) -> Result<(String, u32), WindowError>
+    where
+        I: IntoIterator,
+        I::Item: Display
+    {
         let pc = PopenConfig {
             stdout: Redirection::Pipe,
             stdin: Redirection::Pipe,
             ..Default::default()
         };
-        let options_arr = options
-            .iter()
-            .map(|s| s.replace("\n", ""))
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        let mut call = vec!["rofi", "-dmenu", "-format"]
+        let mut call = ["rofi", "-dmenu", "-format"]
             .iter()
             .map(|s| s.to_string())
             .collect::<Vec<String>>();
 
         call.extend(self.to_args());
         let mut p = Popen::create(&call, pc)?;
-        // Obtain the output from the standard streams.
-        let (entry, _stdout) = p.communicate(Some(&options_arr))?;
-        let entry = entry.unwrap_or("-1".to_string());
+        // options are streamed while rofi already shows the first ones. It stops reading once the
+        // user picked one, so a closed pipe only means the rest isn't needed
+        if let Some(stdin) = p.stdin.take() {
+            match write_options(stdin, options) {
+                Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+                    return Err(PopenError::from(e).into())
+                }
+                _ => {}
+            }
+        }
+        let mut entry = String::new();
+        if let Some(mut stdout) = p.stdout.take() {
+            stdout.read_to_string(&mut entry).map_err(PopenError::from)?;
+        }
         match p.wait() {
-            Ok(_p) => Ok(entry.clone().trim().to_string()),
+            Ok(ExitStatus::Exited(code)) => Ok((entry.trim().to_string(), code)),
+            Ok(_) => Ok((entry.trim().to_string(), 1)),
             Err(e) => Err(e.into())
         }
     }
@@ -165,9 +207,9 @@ impl<'a, 's, 'm> Window<'m> {
             format: ReturnFormat::IntReturn
         }
     }
-    /// set the window's message
-    pub fn message(mut self, msg: &'static str) -> Self {
-        self.message = Some(msg);
+    /// set the window's message, either a borrowed or an owned string
+    pub fn message<M: Into<Cow<'m, str>>>(mut self, msg: M) -> Self {
+        self.message = Some(msg.into());
         self
     }
     /// set the window's location
@@ -204,11 +246,17 @@ impl<'a, 's, 'm> Window<'m> {
     pub fn format(mut self, f: char) -> Self {
         match f {
             's' => self.format = ReturnFormat::StringReturn,
-            'i' | _ => self.format = ReturnFormat::IntReturn
+            _ => self.format = ReturnFormat::IntReturn
         }
         self
     }
 
+    /// show the window once the first `lines` options were read, reading the others while it's
+    /// already shown. Makes windows with a huge number of options appear right away
+    pub fn async_pre_read(self, lines: usize) -> Self {
+        self.add_args(vec!["-async-pre-read".to_string(), lines.to_string()])
+    }
+
     /// add any additional args rofi accepts as an array of strings. These must include any dashes.
     ///
     /// https://gist.github.com/eyalev/a644bb75fdc6f476c2b25d9284a94682
@@ -217,18 +265,164 @@ impl<'a, 's, 'm> Window<'m> {
         self
     }
 
-    /// run the rofi command this window represents
-    pub fn show(self, options: Vec<String>) -> Result<String, WindowError> {
-        let res = self.run_blocking(options);
-        match res {
-            Ok(d) => {
-                return Ok(d);
+    /// run the rofi command this window represents. The options can be anything displayable,
+    /// they're written to rofi one at a time as the iterator yields them
+    pub fn show<I>(self, options: I) -> Result<String, WindowError>
+    where
+        I: IntoIterator,
+        I::Item: Display
+    {
+        self.run_blocking(options)
+    }
+
+    /// run the rofi command this window represents and report what the user did. Unlike `show`
+    /// this identifies the selected option by its index, so options sharing the same text can
+    /// never be mistaken for each other, and it reports custom keybindings (`-kb-custom-<n>`).
+    /// Like `show` the options are streamed to rofi, so the selected index is all that's needed to
+    /// find the option back
+    pub fn select<I>(mut self, options: I) -> Result<Response, WindowError>
+    where
+        I: IntoIterator,
+        I::Item: Display
+    {
+        self.format = ReturnFormat::IndexFilterReturn;
+        let (output, code) = self.run_with_status(options)?;
+        Ok(parse_response(&output, code))
+    }
+
+    /// show `msg` in a rofi error window (`rofi -e`) and block until the user dismisses it.
+    /// Only the window's additional args are passed along, rofi ignores the rest in this mode
+    pub fn show_error(self, msg: String) -> Result<(), WindowError> {
+        let mut call = vec!["rofi".to_string(), "-e".to_string(), msg];
+        call.extend(self.additional_args);
+        let mut p = Popen::create(&call, PopenConfig::default())?;
+        p.wait()?;
+        Ok(())
+    }
+}
+
+/// a single entry shown by rofi together with its row options
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// text shown for the entry
+    pub text: String,
+    /// name or path of the icon shown next to the entry, needs `-show-icons`
+    pub icon: Option<String>,
+    /// hidden text the entry is also matched against when filtering
+    pub meta: Option<String>,
+    /// whether the entry can't be selected, for headers and separators
+    pub nonselectable: bool
+}
+
+impl Row {
+    /// create a row showing `text` without any options
+    pub fn new(text: &str) -> Self {
+        Row {
+            text: text.to_string(),
+            icon: None,
+            meta: None,
+            nonselectable: false
+        }
+    }
+
+    /// set the icon shown next to the row
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// set hidden text the row is also matched against, so it stays visible for that filter
+    pub fn meta(mut self, meta: &str) -> Self {
+        self.meta = Some(meta.to_string());
+        self
+    }
+
+    /// make the row impossible to select, rofi skips over it
+    pub fn nonselectable(mut self, nonselectable: bool) -> Self {
+        self.nonselectable = nonselectable;
+        self
+    }
+}
+
+impl fmt::Display for Row {
+    /// the row as rofi reads it from stdin, options are appended as `\0key\x1fvalue\x1fkey\x1fvalue`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)?;
+        let nonselectable = Some("true".to_string()).filter(|_| self.nonselectable);
+        let options = [("icon", &self.icon), ("meta", &self.meta), ("nonselectable", &nonselectable)];
+        let mut separator = '\0';
+        for (key, value) in options.iter() {
+            if let Some(value) = value {
+                write!(f, "{}{}\x1f{}", separator, key, value)?;
+                separator = '\x1f';
             }
-            Err(e) => Err(e.into())
         }
+        Ok(())
+    }
+}
+
+/// what the user did according to rofi's exit `code` and `output` in the `i f` format: the index of
+/// the selected option, `-1` without one, followed by the typed text
+fn parse_response(output: &str, code: u32) -> Response {
+    let mut parts = output.splitn(2, ' ');
+    let row = parts.next().and_then(|i| i.parse::<usize>().ok());
+    let filter = parts.next().unwrap_or("").to_string();
+    match code {
+        // rofi exits with 10 + n - 1 for custom keybinding n
+        10..=28 => Response::Key {
+            key: code as usize - 9,
+            row,
+            filter
+        },
+        _ if output.is_empty() || code != 0 => Response::Cancel,
+        _ => match row {
+            Some(row) => Response::Row(row),
+            None => Response::Entry(filter)
+        }
+    }
+}
+
+/// write `options` to `writer` the way rofi reads them from stdin, one per line. Newlines inside an
+/// option are dropped so it can't spill over onto the next line
+pub fn write_options<W: Write, I>(writer: W, options: I) -> io::Result<()>
+where
+    I: IntoIterator,
+    I::Item: Display
+{
+    let mut line = SingleLine(BufWriter::with_capacity(64 * 1024, writer));
+    for option in options {
+        write!(line, "{}", option)?;
+        line.0.write_all(b"\n")?;
+    }
+    line.0.flush()
+}
+
+/// writer dropping the newlines written to it
+struct SingleLine<W>(W);
+
+impl<W: Write> Write for SingleLine<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for part in buf.split(|b| *b == b'\n') {
+            self.0.write_all(part)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
     }
 }
 
+/// escape text so it is shown literally in a window using pango markup (`-markup-rows`, `-markup`
+/// or a message)
+pub fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
 trait ToArgs {
     /// convert the type to rofi command line arguments
     fn to_args(&self) -> Vec<String>;
@@ -276,12 +470,13 @@ impl ToArgs for ReturnFormat {
     fn to_args(&self) -> Vec<String> {
         match self {
             ReturnFormat::StringReturn => vec!["s".to_string()],
-            ReturnFormat::IntReturn => vec!["i".to_string()]
+            ReturnFormat::IntReturn => vec!["i".to_string()],
+            ReturnFormat::IndexFilterReturn => vec!["i f".to_string()]
         }
     }
 }
 
-impl<'a, 'm> ToArgs for Window<'m> {
+impl<'m> ToArgs for Window<'m> {
     fn to_args(&self) -> Vec<String> {
         let mut args = Vec::new();
         args.extend(self.format.to_args());
@@ -292,7 +487,7 @@ impl<'a, 'm> ToArgs for Window<'m> {
             args.extend(self.padding.to_args());
             args.extend(self.location.to_args());
         }
-        if let Some(msg) = self.message {
+        if let Some(msg) = &self.message {
             args.extend(vec!["-mesg".to_string(), msg.to_string()]);
         }
         args.extend(vec!["-p".to_string(), self.prompt.clone()]);
@@ -300,3 +495,53 @@ impl<'a, 'm> ToArgs for Window<'m> {
         args
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selected_row() {
+        assert_eq!(parse_response("3 ap", 0), Response::Row(3));
+        assert_eq!(parse_response("0", 0), Response::Row(0));
+    }
+
+    #[test]
+    fn entered_text() {
+        assert_eq!(parse_response("-1 two words", 0), Response::Entry("two words".to_string()));
+        assert_eq!(parse_response("-1", 0), Response::Entry(String::new()));
+    }
+
+    #[test]
+    fn custom_keys() {
+        let key = |key, row, filter: &str| Response::Key {
+            key,
+            row,
+            filter: filter.to_string()
+        };
+        assert_eq!(parse_response("2 ap", 10), key(1, Some(2), "ap"));
+        assert_eq!(parse_response("-1 new", 28), key(19, None, "new"));
+        // a key pressed without any options shown
+        assert_eq!(parse_response("", 12), key(3, None, ""));
+    }
+
+    #[test]
+    fn cancelled() {
+        assert_eq!(parse_response("", 1), Response::Cancel);
+        assert_eq!(parse_response("", 0), Response::Cancel);
+        assert_eq!(parse_response("2 ap", 1), Response::Cancel);
+        assert_eq!(parse_response("2 ap", 29), Response::Cancel);
+    }
+
+    #[test]
+    fn options_stay_on_one_line() {
+        let mut written = Vec::new();
+        let rows = vec![Row::new("two\nlines"), Row::new("one").meta("a\nb")];
+        write_options(&mut written, rows).unwrap();
+        assert_eq!(written, b"twolines\none\0meta\x1fab\n".to_vec());
+
+        let mut written = Vec::new();
+        write_options(&mut written, vec!["\n", "", "x\n\ny"]).unwrap();
+        assert_eq!(written, b"\n\nxy\n".to_vec());
+    }
+}
@@ -0,0 +1,15 @@
+use std::env;
+use std::path::PathBuf;
+
+/// directory rustofi keeps its persisted data in, `$XDG_DATA_HOME/rustofi` falling back to
+/// `~/.local/share/rustofi` as the XDG base directory spec asks
+pub(crate) fn data_dir() -> PathBuf {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+            home.join(".local").join("share")
+        }
+    };
+    base.join("rustofi")
+}
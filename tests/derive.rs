@@ -0,0 +1,74 @@
+//! What `#[derive(RofiMenu)]` and `#[derive(RofiForm)]` generate
+#![cfg(feature = "derive")]
+use rustofi::{Action, RofiForm, RofiMenu};
+
+#[derive(Debug, Clone, PartialEq, RofiMenu)]
+enum Command {
+    Open,
+    #[rofi(label = "Save & quit", icon = "document-save")]
+    SaveQuit,
+    #[rofi(skip)]
+    Hidden
+}
+
+#[derive(Debug, Clone, PartialEq, RofiForm)]
+struct Todo {
+    title: String,
+    due_date: String,
+    #[rofi(label = "Priority (1-5)")]
+    priority: u8,
+    done: bool,
+    #[rofi(skip)]
+    id: usize
+}
+
+fn todo() -> Todo {
+    Todo {
+        title: "<buy> milk".to_string(),
+        due_date: "today".to_string(),
+        priority: 2,
+        done: false,
+        id: 7
+    }
+}
+
+#[test]
+fn menu_entries_leave_out_skipped_variants() {
+    assert_eq!(Command::entries(), vec![Command::Open, Command::SaveQuit]);
+    assert_eq!(Command::labels(), vec!["Open", "Save & quit"]);
+}
+
+#[test]
+fn menu_labels_and_icons() {
+    assert_eq!(Command::Hidden.label(), "Hidden");
+    assert_eq!(Command::Open.icon(), None);
+    assert_eq!(Command::SaveQuit.icon(), Some("document-save".to_string()));
+}
+
+#[test]
+fn menu_actions_escape_labels() {
+    let actions = Action::<Command>::menu_entries();
+    assert_eq!(actions[1].label, "Save &amp; quit");
+    assert_eq!(actions[1].icon, Some("document-save".to_string()));
+}
+
+#[test]
+fn form_fields_follow_the_struct() {
+    let labels: Vec<String> = Todo::fields().into_iter().map(|f| f.label).collect();
+    assert_eq!(labels, vec!["Title", "Due date", "Priority (1-5)", "Done"]);
+}
+
+#[test]
+fn form_fields_read_the_value() {
+    let shown: Vec<String> = Todo::fields().iter().map(|f| (f.display)(&todo())).collect();
+    assert_eq!(shown, vec!["&lt;buy&gt; milk", "today", "2", "no"]);
+}
+
+#[test]
+fn form_toggles_write_the_value() {
+    let mut done = Todo::fields().remove(3);
+    let mut todo = todo();
+    (done.edit)(&mut todo, &done.label).unwrap();
+    assert!(todo.done);
+    assert_eq!(todo.id, 7);
+}